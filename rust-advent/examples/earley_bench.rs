@@ -0,0 +1,171 @@
+//! Compare the day 19 Earley recognizer against the string based one it replaced.
+//!
+//!     cargo run --release --example earley_bench [path/to/input.txt]
+//!
+//! The old version kept whole rules as `String`s in every item, cloned them on every
+//! step, and looked for duplicates with a linear scan of the state set.  It lives on
+//! here with the same algorithm, adapted to the current `Grammar`/`Rule` types and to
+//! scanning literals longer than one byte.  Grammars without recursion are also timed
+//! as a compiled regex, which is what the solver uses for them.
+use advent2020::day_nineteen::earley::Parser;
+use advent2020::day_nineteen::grammar::{self, Grammar, Rule};
+use advent2020::day_nineteen::pattern;
+use std::env;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    lhs: String,
+    rhs: Vec<String>,
+    position: usize,
+    input_start: usize,
+}
+
+impl Item {
+    fn move_position_right(&self) -> Item {
+        Item {
+            lhs: self.lhs.to_string(),
+            rhs: self.rhs.to_vec(),
+            position: self.position + 1,
+            input_start: self.input_start,
+        }
+    }
+}
+
+fn predict_items(rule: &Rule, input_index: usize) -> Vec<Item> {
+    rule.sub_rules
+        .iter()
+        .map(|sub_rule| Item {
+            lhs: rule.symbol.to_string(),
+            rhs: sub_rule.to_vec(),
+            position: 0,
+            input_start: input_index,
+        })
+        .collect()
+}
+
+fn add(sets: &mut [Vec<Item>], set_index: usize, item: &Item) {
+    if set_index < sets.len() && !sets[set_index].iter().any(|i| i == item) {
+        sets[set_index].push(item.clone());
+    }
+}
+
+fn old_recognize(grammar: &Grammar, input: &str) -> bool {
+    let mut sets: Vec<Vec<Item>> = vec![vec![]; input.len() + 1];
+    let start = Item {
+        lhs: String::new(),
+        rhs: vec![grammar.start_symbol.to_string()],
+        position: 0,
+        input_start: 0,
+    };
+    add(&mut sets, 0, &start);
+
+    for state_index in 0..input.len() + 1 {
+        let mut inner_index = 0;
+        while inner_index < sets[state_index].len() {
+            let cur_item = sets[state_index][inner_index].clone();
+            inner_index += 1;
+            let next_symbol = match cur_item.rhs.get(cur_item.position) {
+                None => {
+                    let completed: Vec<Item> = sets[cur_item.input_start]
+                        .iter()
+                        .filter(|i| i.rhs.get(i.position) == Some(&cur_item.lhs))
+                        .map(|i| i.move_position_right())
+                        .collect();
+                    for item in &completed {
+                        add(&mut sets, state_index, item);
+                    }
+                    continue;
+                }
+                Some(symbol) => symbol,
+            };
+            let next_rule = &grammar.rules[next_symbol];
+            match &next_rule.terminal {
+                None => {
+                    for item in &predict_items(next_rule, state_index) {
+                        add(&mut sets, state_index, item);
+                    }
+                }
+                Some(terminal) => {
+                    if input.as_bytes()[state_index..].starts_with(terminal.as_bytes()) {
+                        let scanned = Item {
+                            lhs: next_rule.symbol.to_string(),
+                            rhs: vec![terminal.to_string()],
+                            position: 1,
+                            input_start: state_index,
+                        };
+                        add(&mut sets, state_index + terminal.len(), &scanned);
+                    }
+                }
+            }
+        }
+    }
+    sets[input.len()]
+        .iter()
+        .any(|i| i.lhs.is_empty() && i.position == 1 && i.input_start == 0)
+}
+
+// best of a few runs, to keep noise from other processes out of it
+fn time(count: impl Fn() -> usize) -> (usize, Duration) {
+    let mut best = Duration::from_secs(u64::MAX);
+    let mut matches = 0;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        matches = count();
+        best = best.min(start.elapsed());
+    }
+    (matches, best)
+}
+
+fn main() {
+    let path = env::args().nth(1).map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../inputs/19/input.txt")
+    });
+    let content = std::fs::read_to_string(&path).expect("could not read the input");
+    let (rules, messages) = grammar::split_input(&content);
+    let part_one = Grammar::from_input(&rules).expect("could not parse the rules");
+    let mut part_two = part_one.clone();
+    part_two
+        .apply_overrides("8: 42 | 42 8\n11: 42 31 | 42 11 31")
+        .expect("part two needs rules 42 and 31");
+
+    println!("{} messages from {}", messages.len(), path.display());
+    println!(
+        "{:<10} {:>8} {:>12} {:>12} {:>8} {:>12}",
+        "grammar", "matches", "old", "new", "speedup", "regex"
+    );
+    for (name, grammar) in &[("part one", part_one), ("part two", part_two)] {
+        let (old_matches, old_time) = time(|| {
+            messages
+                .iter()
+                .filter(|m| old_recognize(grammar, m))
+                .count()
+        });
+        let (new_matches, new_time) = time(|| {
+            let parser = Parser::new(grammar);
+            messages.iter().filter(|m| parser.recognize(m)).count()
+        });
+        assert_eq!(old_matches, new_matches, "old and new disagree on {}", name);
+        let regex_time = match pattern::compile(grammar) {
+            None => String::from("-"),
+            Some(regex) => {
+                let (regex_matches, regex_time) =
+                    time(|| messages.iter().filter(|m| regex.is_match(m)).count());
+                assert_eq!(regex_matches, new_matches, "regex disagrees on {}", name);
+                format!("{:?}", regex_time)
+            }
+        };
+        println!(
+            "{:<10} {:>8} {:>12?} {:>12?} {:>7.1}x {:>12}",
+            name,
+            new_matches,
+            old_time,
+            new_time,
+            old_time.as_secs_f64() / new_time.as_secs_f64(),
+            regex_time
+        );
+    }
+}
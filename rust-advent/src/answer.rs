@@ -0,0 +1,52 @@
+use std::fmt;
+
+/// The value produced by a solver part.  Most puzzles want a number, but some
+/// ask for a string of labels or a list of lines instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(usize),
+    BigInt(u128),
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(val) => write!(f, "{}", val),
+            Answer::BigInt(val) => write!(f, "{}", val),
+            Answer::Text(val) => write!(f, "{}", val),
+            Answer::Lines(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(val: usize) -> Answer {
+        Answer::Int(val)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(val: u128) -> Answer {
+        Answer::BigInt(val)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(val: String) -> Answer {
+        Answer::Text(val)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(val: &str) -> Answer {
+        Answer::Text(val.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(lines: Vec<String>) -> Answer {
+        Answer::Lines(lines)
+    }
+}
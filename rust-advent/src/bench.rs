@@ -0,0 +1,187 @@
+use super::registry::{SolverEntry, SolverOption};
+use super::runner::panic_message;
+use super::{SolveError, Solver};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    FromInput,
+    PartOne,
+    PartTwo,
+}
+
+impl Phase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Phase::FromInput => "from_input",
+            Phase::PartOne => "part_one",
+            Phase::PartTwo => "part_two",
+        }
+    }
+}
+
+/// Every sample taken for one phase of one day.  If the phase failed, sampling
+/// stops and the error is kept instead.  A part that isn't implemented, or doesn't
+/// apply to the input, is marked as skipped and isn't an error.
+#[derive(Debug, Clone)]
+pub struct PhaseTiming {
+    pub day: usize,
+    pub phase: Phase,
+    pub samples: Vec<Duration>,
+    pub error: Option<String>,
+    pub skipped: bool,
+}
+
+impl PhaseTiming {
+    fn new(day: usize, phase: Phase) -> PhaseTiming {
+        PhaseTiming {
+            day,
+            phase,
+            samples: vec![],
+            error: None,
+            skipped: false,
+        }
+    }
+
+    fn sorted(&self) -> Vec<Duration> {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        sorted
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.samples.iter().min().copied()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+
+    pub fn median(&self) -> Option<Duration> {
+        let sorted = self.sorted();
+        let len = sorted.len();
+        if len == 0 {
+            None
+        } else if len % 2 == 1 {
+            Some(sorted[len / 2])
+        } else {
+            Some((sorted[len / 2 - 1] + sorted[len / 2]) / 2)
+        }
+    }
+}
+
+fn time_part(timing: &mut PhaseTiming, solver: &dyn Solver, runs: usize) {
+    for _ in 0..runs {
+        let phase = timing.phase;
+        let start = Instant::now();
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            let result = match phase {
+                Phase::PartOne => solver.part_one(),
+                _ => solver.part_two(),
+            };
+            result.map(|_| ())
+        }));
+        let elapsed = start.elapsed();
+        match outcome {
+            Ok(Ok(())) => timing.samples.push(elapsed),
+            Ok(Err(SolveError::NotImplemented)) | Ok(Err(SolveError::NotApplicable(_))) => {
+                timing.skipped = true;
+                return;
+            }
+            Ok(Err(err)) => {
+                timing.error = Some(err.to_string());
+                return;
+            }
+            Err(payload) => {
+                timing.error = Some(panic_message(payload));
+                return;
+            }
+        }
+    }
+}
+
+/// Run `from_input`, `part_one` and `part_two` for a day `runs` times each
+pub fn bench_day(
+    entry: &SolverEntry,
+    input: &str,
+    options: &[SolverOption],
+    runs: usize,
+) -> Vec<PhaseTiming> {
+    let mut parse = PhaseTiming::new(entry.day, Phase::FromInput);
+    let mut part_one = PhaseTiming::new(entry.day, Phase::PartOne);
+    let mut part_two = PhaseTiming::new(entry.day, Phase::PartTwo);
+
+    // keep the last solver that was built so the parts have something to run against
+    let mut solver = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let built = panic::catch_unwind(AssertUnwindSafe(|| entry.build_with(input, options)));
+        let elapsed = start.elapsed();
+        match built {
+            Ok(Ok(built)) => {
+                parse.samples.push(elapsed);
+                solver = Some(built);
+            }
+            Ok(Err(err)) => {
+                parse.error = Some(err.to_string());
+                break;
+            }
+            Err(payload) => {
+                parse.error = Some(panic_message(payload));
+                break;
+            }
+        }
+    }
+
+    match (&parse.error, solver) {
+        (None, Some(solver)) => {
+            time_part(&mut part_one, solver.as_ref(), runs);
+            time_part(&mut part_two, solver.as_ref(), runs);
+        }
+        _ => {
+            let err = parse
+                .error
+                .clone()
+                .unwrap_or_else(|| String::from("no runs requested"));
+            part_one.error = Some(err.clone());
+            part_two.error = Some(err);
+        }
+    }
+
+    vec![parse, part_one, part_two]
+}
+
+/// Every phase of a day that has no input file, marked as skipped
+pub fn skipped_day(day: usize) -> Vec<PhaseTiming> {
+    [Phase::FromInput, Phase::PartOne, Phase::PartTwo]
+        .iter()
+        .map(|phase| PhaseTiming {
+            skipped: true,
+            ..PhaseTiming::new(day, *phase)
+        })
+        .collect()
+}
+
+/// Same as `bench_day`, but reads the input from a file first
+pub fn bench_day_file(
+    entry: &SolverEntry,
+    path: &Path,
+    options: &[SolverOption],
+    runs: usize,
+) -> Vec<PhaseTiming> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => bench_day(entry, &content, options, runs),
+        Err(err) => {
+            let err = format!("could not read {}: {}", path.display(), err);
+            [Phase::FromInput, Phase::PartOne, Phase::PartTwo]
+                .iter()
+                .map(|phase| PhaseTiming {
+                    error: Some(err.clone()),
+                    ..PhaseTiming::new(entry.day, *phase)
+                })
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use super::registry::{SolverEntry, SolverOption};
+use super::runner::{self, PartResult, Status};
+use std::path::{Path, PathBuf};
+
+/// Known answers for one input, read from a sidecar `.answers` file:
+///
+/// ```text
+/// part1: 306
+/// part2: 291
+/// ```
+///
+/// Repeating a key adds another line to a multi-line answer.  Parts without a
+/// line are not checked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpectedAnswers {
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+impl ExpectedAnswers {
+    pub fn parse(text: &str) -> Result<ExpectedAnswers, String> {
+        let mut expected = ExpectedAnswers::default();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match line.find(':') {
+                Some(idx) => (line[..idx].trim(), line[idx + 1..].trim()),
+                None => return Err(format!("line {}: expected 'partN: answer'", line_no + 1)),
+            };
+            let slot = match key {
+                "part1" => &mut expected.part_one,
+                "part2" => &mut expected.part_two,
+                _ => return Err(format!("line {}: unknown key '{}'", line_no + 1, key)),
+            };
+            *slot = Some(match slot.take() {
+                None => value.to_string(),
+                Some(previous) => format!("{}\n{}", previous, value),
+            });
+        }
+        Ok(expected)
+    }
+
+    pub fn get(&self, part: usize) -> Option<&String> {
+        match part {
+            1 => self.part_one.as_ref(),
+            2 => self.part_two.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// The answers file that goes with an input, e.g. `inputs/22/test.answers`
+pub fn answers_path(input: &Path) -> PathBuf {
+    input.with_extension("answers")
+}
+
+/// One part's result lined up against its expected answer
+#[derive(Debug, Clone)]
+pub struct CheckOutcome {
+    pub result: PartResult,
+    pub expected: Option<String>,
+}
+
+impl CheckOutcome {
+    pub fn actual(&self) -> String {
+        match (&self.result.answer, &self.result.error) {
+            (Some(answer), _) => answer.to_string(),
+            (None, Some(error)) => format!("<{}>", error),
+            (None, None) => String::from("<no answer>"),
+        }
+    }
+
+    /// An unchecked part still fails if it errored or panicked, since there's no answer
+    /// that could be right
+    pub fn passed(&self) -> bool {
+        match (&self.expected, &self.result.answer) {
+            (None, _) => self.result.status != Status::Error,
+            (Some(expected), Some(answer)) => &answer.to_string() == expected,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Run a day against an input and compare both parts with the expected answers
+pub fn check_day(
+    entry: &SolverEntry,
+    input: &str,
+    options: &[SolverOption],
+    expected: &ExpectedAnswers,
+) -> Vec<CheckOutcome> {
+    runner::run_day(entry, input, options)
+        .into_iter()
+        .map(|result| CheckOutcome {
+            expected: expected.get(result.part).cloned(),
+            result,
+        })
+        .collect()
+}
+
+/// Check an input file against the `.answers` file next to it
+pub fn check_file(
+    entry: &SolverEntry,
+    input_path: &Path,
+    options: &[SolverOption],
+) -> Result<Vec<CheckOutcome>, String> {
+    let path = answers_path(input_path);
+    let text = std::fs::read_to_string(&path)
+        .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+    let expected =
+        ExpectedAnswers::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let input = std::fs::read_to_string(input_path)
+        .map_err(|err| format!("could not read {}: {}", input_path.display(), err))?;
+    Ok(check_day(entry, &input, options, &expected))
+}
@@ -0,0 +1,247 @@
+use super::grammar::Grammar;
+use std::collections::{HashMap, HashSet};
+
+// while converting, bodies can still hold characters; by the end only `A -> a` does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Symbol {
+    Rule(usize),
+    Char(char),
+}
+
+type Production = (usize, Vec<Symbol>);
+
+/// A grammar in Chomsky normal form, over single characters: every rule is `A -> B C`
+/// or `A -> a`, and only the start can match nothing.  It is a second way of recognizing
+/// messages, independent of the Earley parser, since none of the conversion is shared.
+#[derive(Debug)]
+pub struct Cnf {
+    rules: usize,
+    start: usize,
+    accepts_empty: bool,
+    /// Rules with `A -> a`, by character
+    terminals: HashMap<char, Vec<usize>>,
+    /// `(A, C)` for each `A -> B C`, by B
+    binary: Vec<Vec<(usize, usize)>>,
+}
+
+impl Cnf {
+    pub fn new(grammar: &Grammar) -> Cnf {
+        let mut names: Vec<&String> = grammar.rules.keys().collect();
+        names.sort_by_key(|name| (name.len(), name.to_string()));
+        let ids: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (name.as_str(), id))
+            .collect();
+        let mut rules = names.len();
+
+        let mut productions: Vec<Production> = vec![];
+        for (name, rule) in &grammar.rules {
+            let lhs = ids[name.as_str()];
+            match &rule.terminal {
+                Some(literal) => {
+                    productions.push((lhs, literal.chars().map(Symbol::Char).collect()))
+                }
+                None => {
+                    for sub_rule in &rule.sub_rules {
+                        let body = sub_rule
+                            .iter()
+                            .map(|used| Symbol::Rule(ids[used.as_str()]))
+                            .collect();
+                        productions.push((lhs, body));
+                    }
+                }
+            }
+        }
+
+        // a fresh start rule, so it never shows up on the right and can keep `S -> ε`
+        let start = rules;
+        rules += 1;
+        productions.push((
+            start,
+            vec![Symbol::Rule(ids[grammar.start_symbol.as_str()])],
+        ));
+
+        // characters in longer bodies get a rule of their own
+        let mut char_rules: HashMap<char, usize> = HashMap::new();
+        for (_, body) in productions.iter_mut().filter(|(_, body)| body.len() > 1) {
+            for symbol in body.iter_mut() {
+                if let Symbol::Char(c) = *symbol {
+                    let id = *char_rules.entry(c).or_insert_with(|| {
+                        rules += 1;
+                        rules - 1
+                    });
+                    *symbol = Symbol::Rule(id);
+                }
+            }
+        }
+        for (c, id) in &char_rules {
+            productions.push((*id, vec![Symbol::Char(*c)]));
+        }
+
+        // A -> B C D ... becomes A -> B A1, A1 -> C A2, ...
+        let mut binary_productions = vec![];
+        for (lhs, body) in productions {
+            let mut lhs = lhs;
+            let mut rest = &body[..];
+            while rest.len() > 2 {
+                let next = rules;
+                rules += 1;
+                binary_productions.push((lhs, vec![rest[0], Symbol::Rule(next)]));
+                lhs = next;
+                rest = &rest[1..];
+            }
+            binary_productions.push((lhs, rest.to_vec()));
+        }
+
+        // drop empty bodies, adding a copy of each body with the rules that can be empty
+        // left out instead
+        let nullable = nullable_rules(rules, &binary_productions);
+        let mut without_empty: HashSet<Production> = HashSet::new();
+        for (lhs, body) in &binary_productions {
+            let optional: Vec<bool> = body
+                .iter()
+                .map(|symbol| matches!(symbol, Symbol::Rule(id) if nullable[*id]))
+                .collect();
+            for mask in 0..1usize << body.len() {
+                // only leave out what can be left out
+                if (0..body.len()).any(|idx| mask & (1 << idx) != 0 && !optional[idx]) {
+                    continue;
+                }
+                let kept: Vec<Symbol> = (0..body.len())
+                    .filter(|idx| mask & (1 << idx) == 0)
+                    .map(|idx| body[idx])
+                    .collect();
+                if !kept.is_empty() {
+                    without_empty.insert((*lhs, kept));
+                }
+            }
+        }
+
+        // replace each A -> B with everything B (or anything B leads to this way) has
+        let mut units: Vec<Vec<usize>> = vec![vec![]; rules];
+        for (lhs, body) in &without_empty {
+            if let [Symbol::Rule(used)] = body[..] {
+                units[*lhs].push(used);
+            }
+        }
+        let mut terminals: HashMap<char, Vec<usize>> = HashMap::new();
+        let mut binary: Vec<Vec<(usize, usize)>> = vec![vec![]; rules];
+        let mut by_lhs: Vec<Vec<&Vec<Symbol>>> = vec![vec![]; rules];
+        for (lhs, body) in &without_empty {
+            by_lhs[*lhs].push(body);
+        }
+        for lhs in 0..rules {
+            let mut reached = vec![false; rules];
+            let mut to_visit = vec![lhs];
+            while let Some(id) = to_visit.pop() {
+                if reached[id] {
+                    continue;
+                }
+                reached[id] = true;
+                to_visit.extend(&units[id]);
+                for body in &by_lhs[id] {
+                    match body[..] {
+                        [Symbol::Char(c)] => terminals.entry(c).or_default().push(lhs),
+                        [Symbol::Rule(left), Symbol::Rule(right)] => {
+                            binary[left].push((lhs, right))
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+        for rules in terminals.values_mut() {
+            rules.sort_unstable();
+            rules.dedup();
+        }
+        for pairs in binary.iter_mut() {
+            pairs.sort_unstable();
+            pairs.dedup();
+        }
+
+        Cnf {
+            rules,
+            start,
+            accepts_empty: nullable[start],
+            terminals,
+            binary,
+        }
+    }
+
+    /// Whether `input` can be derived from the start rule, filling in the CYK table of
+    /// which rules match each slice of the input
+    pub fn recognize(&self, input: &str) -> bool {
+        let chars: Vec<char> = input.chars().collect();
+        let n = chars.len();
+        if n == 0 {
+            return self.accepts_empty;
+        }
+
+        // table[length - 1][start] is the set of rules matching that slice, as bits
+        let words = self.rules.div_ceil(64);
+        let mut table = vec![vec![vec![0u64; words]; n]; n];
+        for (idx, c) in chars.iter().enumerate() {
+            for id in self.terminals.get(c).into_iter().flatten() {
+                table[0][idx][id / 64] |= 1 << (id % 64);
+            }
+        }
+        for length in 2..=n {
+            for start in 0..=n - length {
+                let mut cell = vec![0u64; words];
+                for split in 1..length {
+                    let left = &table[split - 1][start];
+                    let right = &table[length - split - 1][start + split];
+                    for left_id in ones(left) {
+                        for (lhs, right_id) in &self.binary[left_id] {
+                            if right[right_id / 64] & (1 << (right_id % 64)) != 0 {
+                                cell[lhs / 64] |= 1 << (lhs % 64);
+                            }
+                        }
+                    }
+                }
+                table[length - 1][start] = cell;
+            }
+        }
+        table[n - 1][0][self.start / 64] & (1 << (self.start % 64)) != 0
+    }
+}
+
+fn ones(bits: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    bits.iter().enumerate().flat_map(|(word_idx, word)| {
+        let mut word = *word;
+        std::iter::from_fn(move || {
+            if word == 0 {
+                return None;
+            }
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(word_idx * 64 + bit)
+        })
+    })
+}
+
+fn nullable_rules(rules: usize, productions: &[Production]) -> Vec<bool> {
+    let mut nullable = vec![false; rules];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (lhs, body) in productions {
+            if !nullable[*lhs]
+                && body
+                    .iter()
+                    .all(|symbol| matches!(symbol, Symbol::Rule(id) if nullable[*id]))
+            {
+                nullable[*lhs] = true;
+                changed = true;
+            }
+        }
+    }
+    nullable
+}
+
+/// Whether `input` matches the grammar, converting it to CNF first.  Build a `Cnf` once
+/// instead when checking lots of messages.
+pub fn recognize(grammar: &Grammar, input: &str) -> bool {
+    Cnf::new(grammar).recognize(input)
+}
@@ -0,0 +1,425 @@
+use super::grammar::Grammar;
+use super::tree::ParseTree;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// rules are numbered once up front, so items can refer to them without any strings
+type SymbolId = usize;
+
+/// One alternative of one rule, with its symbols interned
+#[derive(Debug)]
+struct Production {
+    lhs: SymbolId,
+    rhs: Vec<SymbolId>,
+    alternative: usize, // which of the rule's alternatives this is
+}
+
+/// How far through a production we are, and where in the input it started.  Small
+/// enough to copy around and hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Item {
+    production: usize,
+    position: usize,
+    start: usize,
+}
+
+impl Item {
+    fn advance(self) -> Item {
+        Item {
+            position: self.position + 1,
+            ..self
+        }
+    }
+}
+
+/// Items in the order they were added (they get processed in that order), plus a hash
+/// set so that checking for duplicates doesn't mean scanning the whole list
+#[derive(Debug, Default)]
+struct StateSet {
+    items: Vec<Item>,
+    seen: HashSet<Item>,
+}
+
+impl StateSet {
+    fn add(&mut self, item: Item) {
+        if self.seen.insert(item) {
+            self.items.push(item);
+        }
+    }
+}
+
+/// A grammar compiled for Earley parsing.  Build it once and reuse it for every message.
+pub struct Parser<'a> {
+    grammar: &'a Grammar,
+    names: Vec<&'a str>,             // SymbolId -> rule name
+    terminals: Vec<Option<&'a str>>, // SymbolId -> literal, for terminal rules
+    productions: Vec<Production>,
+    by_lhs: Vec<Vec<usize>>, // SymbolId -> its productions
+    nullable: Vec<bool>,     // SymbolId -> whether it can match the empty string
+    accept: usize,           // production for an unnamed rule that just wants the start symbol
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(grammar: &'a Grammar) -> Parser<'a> {
+        // sorted so that ids don't depend on hash order
+        let mut names: Vec<&str> = grammar.rules.keys().map(|name| name.as_str()).collect();
+        names.sort();
+        let ids: HashMap<&str, SymbolId> = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (*name, id))
+            .collect();
+        let terminals = names
+            .iter()
+            .map(|name| grammar.rules[*name].terminal.as_deref())
+            .collect();
+
+        let mut productions = vec![];
+        let mut by_lhs = vec![vec![]; names.len()];
+        for (lhs, name) in names.iter().enumerate() {
+            for (alternative, sub_rule) in grammar.rules[*name].sub_rules.iter().enumerate() {
+                by_lhs[lhs].push(productions.len());
+                productions.push(Production {
+                    lhs,
+                    rhs: sub_rule.iter().map(|symbol| ids[symbol.as_str()]).collect(),
+                    alternative,
+                });
+            }
+        }
+        // starting from this rather than from the start rule's own productions means a
+        // terminal start rule still gets scanned.  Its lhs is an id no real rule has.
+        let accept = productions.len();
+        productions.push(Production {
+            lhs: names.len(),
+            rhs: vec![ids[grammar.start_symbol.as_str()]],
+            alternative: 0,
+        });
+
+        let nullable = nullable_symbols(&productions, names.len() + 1);
+        Parser {
+            grammar,
+            names,
+            terminals,
+            productions,
+            by_lhs,
+            nullable,
+            accept,
+        }
+    }
+
+    fn next_symbol(&self, item: &Item) -> Option<SymbolId> {
+        self.productions[item.production]
+            .rhs
+            .get(item.position)
+            .copied()
+    }
+
+    fn chart(&self, input: &str) -> Vec<StateSet> {
+        debug!("=== PARSING STRING: {} ===", input);
+
+        let mut sets: Vec<StateSet> = (0..=input.len()).map(|_| StateSet::default()).collect();
+        sets[0].add(Item {
+            production: self.accept,
+            position: 0,
+            start: 0,
+        });
+
+        for state_index in 0..sets.len() {
+            let mut inner_index = 0;
+            while inner_index < sets[state_index].items.len() {
+                let item = sets[state_index].items[inner_index];
+                inner_index += 1;
+                let symbol = match self.next_symbol(&item) {
+                    Some(symbol) => symbol,
+                    None => {
+                        // COMPLETE: this item's rule has matched, so go back to the set it started
+                        // in and move along everything that was waiting for it
+                        let lhs = self.productions[item.production].lhs;
+                        let completed: Vec<Item> = sets[item.start]
+                            .items
+                            .iter()
+                            .filter(|waiting| self.next_symbol(waiting) == Some(lhs))
+                            .map(|waiting| waiting.advance())
+                            .collect();
+                        for completed_item in completed {
+                            sets[state_index].add(completed_item);
+                        }
+                        continue;
+                    }
+                };
+                match self.terminals[symbol] {
+                    None => {
+                        // PREDICT: add the productions of the next rule to the current state set
+                        for production in &self.by_lhs[symbol] {
+                            sets[state_index].add(Item {
+                                production: *production,
+                                position: 0,
+                                start: state_index,
+                            });
+                        }
+                        // if the rule can match nothing, it may already have completed in this set
+                        // before this item came along to wait for it, so step over it now
+                        // (Aycock and Horspool's fix for empty rules)
+                        if self.nullable[symbol] {
+                            sets[state_index].add(item.advance());
+                        }
+                    }
+                    Some(literal) => {
+                        // SCAN: if the whole literal matches the input here, then move the item past
+                        // it and into the state set where the literal ends.  State sets are per
+                        // byte, so multi-byte characters just leave the sets in between empty.
+                        if input.as_bytes()[state_index..].starts_with(literal.as_bytes()) {
+                            sets[state_index + literal.len()].add(item.advance());
+                        }
+                    }
+                }
+            }
+        }
+        sets
+    }
+
+    fn is_success(&self, sets: &[StateSet]) -> bool {
+        let accepted = Item {
+            production: self.accept,
+            position: 1,
+            start: 0,
+        };
+        sets[sets.len() - 1].seen.contains(&accepted)
+    }
+
+    /// True if `input` can be derived from the grammar's start symbol
+    pub fn recognize(&self, input: &str) -> bool {
+        self.is_success(&self.chart(input))
+    }
+
+    /// Up to `limit` derivations of `input` from the start symbol, as parse trees.  An
+    /// empty list means the input doesn't match.
+    pub fn derivations(&self, input: &str, limit: usize) -> Vec<ParseTree> {
+        let sets = self.chart(input);
+        if limit == 0 || !self.is_success(&sets) {
+            return vec![];
+        }
+        let start = self.productions[self.accept].rhs[0];
+        Forest::from_chart(self, &sets, input).trees(start, 0, input.len(), limit, &mut vec![])
+    }
+
+    /// `None` if `input` matches, otherwise where and why it stopped matching
+    pub fn diagnose(&self, input: &str) -> Option<ParseFailure> {
+        let sets = self.chart(input);
+        if self.is_success(&sets) {
+            return None;
+        }
+        // set 0 always has the start rule in it, so there is always somewhere to point at
+        let position = (0..sets.len())
+            .rev()
+            .find(|idx| !sets[*idx].items.is_empty())
+            .unwrap_or(0);
+
+        let mut expected: Vec<String> = sets[position]
+            .items
+            .iter()
+            .filter_map(|item| self.next_symbol(item))
+            .filter_map(|symbol| self.terminals[symbol])
+            .map(|literal| literal.to_string())
+            .collect();
+        expected.sort();
+        expected.dedup();
+
+        Some(ParseFailure {
+            position,
+            column: input[..position].chars().count() + 1,
+            found: input[position..].chars().next(),
+            expected,
+        })
+    }
+}
+
+// a symbol is nullable if one of its productions is all nullable symbols (which includes
+// having none at all).  Keep going until nothing new turns up.
+fn nullable_symbols(productions: &[Production], symbol_count: usize) -> Vec<bool> {
+    let mut nullable = vec![false; symbol_count];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for production in productions {
+            if !nullable[production.lhs] && production.rhs.iter().all(|symbol| nullable[*symbol]) {
+                nullable[production.lhs] = true;
+                changed = true;
+            }
+        }
+    }
+    nullable
+}
+
+/// Earley recognizer: true if `input` can be derived from the grammar's start symbol
+pub fn recognize(grammar: &Grammar, input: &str) -> bool {
+    Parser::new(grammar).recognize(input)
+}
+
+/// See `Parser::derivations`
+pub fn derivations(grammar: &Grammar, input: &str, limit: usize) -> Vec<ParseTree> {
+    Parser::new(grammar).derivations(input, limit)
+}
+
+/// See `Parser::diagnose`
+pub fn diagnose(grammar: &Grammar, input: &str) -> Option<ParseFailure> {
+    Parser::new(grammar).diagnose(input)
+}
+
+/// Why a message didn't match: the furthest point any rule got to, and the terminals
+/// that could have carried on from there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailure {
+    pub position: usize, // byte offset into the message
+    pub column: usize,   // 1-based, in characters
+    pub found: Option<char>,
+    pub expected: Vec<String>,
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.found {
+            Some(c) => write!(f, "unexpected {:?} at column {}", c, self.column)?,
+            None => write!(f, "unexpected end of input at column {}", self.column)?,
+        }
+        let quoted: Vec<String> = self
+            .expected
+            .iter()
+            .map(|literal| format!("'{}'", literal))
+            .collect();
+        match quoted.split_last() {
+            None => write!(f, ", expected end of input"),
+            Some((last, [])) => write!(f, ", expected {}", last),
+            Some((last, rest)) => write!(f, ", expected {} or {}", rest.join(", "), last),
+        }
+    }
+}
+
+type Span = (SymbolId, usize, usize);
+
+/// Every completed item in the chart, by the rule it completed and the span of input it
+/// covered.  Walking this top down gives back the derivations.  Terminals never get items
+/// of their own, so they are checked against the input directly.
+struct Forest<'p, 'a> {
+    parser: &'p Parser<'a>,
+    input: &'p str,
+    completed: HashMap<Span, Vec<usize>>, // span -> productions that matched it
+    ends: HashMap<(SymbolId, usize), Vec<usize>>, // where each rule can end, by start position
+}
+
+impl<'p, 'a> Forest<'p, 'a> {
+    fn from_chart(parser: &'p Parser<'a>, sets: &[StateSet], input: &'p str) -> Forest<'p, 'a> {
+        let mut completed: HashMap<Span, Vec<usize>> = HashMap::new();
+        let mut ends: HashMap<(SymbolId, usize), Vec<usize>> = HashMap::new();
+        for (end, set) in sets.iter().enumerate() {
+            for item in set
+                .items
+                .iter()
+                .filter(|item| parser.next_symbol(item).is_none())
+            {
+                let lhs = parser.productions[item.production].lhs;
+                let productions = completed.entry((lhs, item.start, end)).or_default();
+                if productions.is_empty() {
+                    ends.entry((lhs, item.start)).or_default().push(end);
+                }
+                productions.push(item.production);
+            }
+        }
+        Forest {
+            parser,
+            input,
+            completed,
+            ends,
+        }
+    }
+
+    fn literal_at(&self, symbol: SymbolId, start: usize) -> Option<usize> {
+        self.parser.terminals[symbol]
+            .filter(|literal| self.input.as_bytes()[start..].starts_with(literal.as_bytes()))
+            .map(|literal| start + literal.len())
+    }
+
+    // `path` holds the spans being derived above this one.  Coming back to one of them means
+    // going round a cycle of rules without using up any input, which would never end.
+    fn trees(
+        &self,
+        symbol: SymbolId,
+        start: usize,
+        end: usize,
+        limit: usize,
+        path: &mut Vec<Span>,
+    ) -> Vec<ParseTree> {
+        let name = self.parser.names[symbol];
+        if let Some(literal) = self.parser.terminals[symbol] {
+            if self.literal_at(symbol, start) != Some(end) {
+                return vec![];
+            }
+            return vec![ParseTree::Leaf {
+                symbol: name.to_string(),
+                literal: literal.to_string(),
+            }];
+        }
+
+        let span = (symbol, start, end);
+        let productions = match self.completed.get(&span) {
+            Some(productions) if !path.contains(&span) => productions,
+            _ => return vec![],
+        };
+        path.push(span);
+        let mut trees = vec![];
+        for production in productions.iter().map(|idx| &self.parser.productions[*idx]) {
+            for children in self.sequences(&production.rhs, start, end, limit - trees.len(), path) {
+                trees.push(ParseTree::Node {
+                    symbol: name.to_string(),
+                    alternative: production.alternative,
+                    rhs: self.parser.grammar.rules[name].sub_rules[production.alternative].clone(),
+                    children,
+                });
+            }
+            if trees.len() >= limit {
+                break;
+            }
+        }
+        path.pop();
+        trees
+    }
+
+    // every way of splitting start..end between the symbols of `rhs`, in order
+    fn sequences(
+        &self,
+        rhs: &[SymbolId],
+        start: usize,
+        end: usize,
+        limit: usize,
+        path: &mut Vec<Span>,
+    ) -> Vec<Vec<ParseTree>> {
+        let (symbol, rest) = match rhs.split_first() {
+            None if start == end => return vec![vec![]],
+            None => return vec![],
+            Some((symbol, rest)) => (*symbol, rest),
+        };
+        let ends = match self.literal_at(symbol, start) {
+            Some(literal_end) => vec![literal_end],
+            None => self.ends.get(&(symbol, start)).cloned().unwrap_or_default(),
+        };
+        let mut sequences = vec![];
+        for mid in ends.into_iter().filter(|mid| *mid <= end) {
+            let heads = self.trees(symbol, start, mid, limit, path);
+            if heads.is_empty() {
+                continue;
+            }
+            let tails = self.sequences(rest, mid, end, limit, path);
+            for head in &heads {
+                for tail in &tails {
+                    let mut sequence = vec![head.clone()];
+                    sequence.extend(tail.iter().cloned());
+                    sequences.push(sequence);
+                    if sequences.len() >= limit {
+                        return sequences;
+                    }
+                }
+            }
+        }
+        sequences
+    }
+}
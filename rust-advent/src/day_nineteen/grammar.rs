@@ -0,0 +1,348 @@
+use super::DAY;
+use crate::ParseError;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+pub const DEFAULT_START: &str = "0";
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub symbol: String,
+    pub sub_rules: Vec<Vec<String>>,
+    pub terminal: Option<String>,
+}
+
+/// A rule as it was read, along with where each rightside symbol appeared so that
+/// undefined ones can be pointed at
+struct RuleLine<'a> {
+    rule: Rule,
+    line_idx: usize,
+    line: &'a str,
+    symbol_cols: Vec<(String, usize)>,
+}
+
+/// Something odd about a grammar that doesn't stop it from being parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrammarWarning {
+    /// Rules that can never be used starting from the start symbol
+    Unreachable(Vec<String>),
+    /// More than one rule that no other rule refers to, so any of them could be meant as the start
+    MultipleRoots(Vec<String>),
+}
+
+impl fmt::Display for GrammarWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarWarning::Unreachable(symbols) => write!(
+                f,
+                "rules {} can't be reached from the start rule",
+                symbols.join(", ")
+            ),
+            GrammarWarning::MultipleRoots(symbols) => write!(
+                f,
+                "rules {} are not used by any other rule, so any of them could be the start",
+                symbols.join(", ")
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    pub rules: HashMap<String, Rule>, // map of Symbol -> array of symbol arrays
+    pub start_symbol: String,
+}
+
+impl Grammar {
+    /// Read the rules, starting from rule `0`
+    pub fn from_input(input: &str) -> Result<Grammar, ParseError> {
+        let rule_lines = parse_rule_lines(input)?;
+        let rules: HashMap<String, Rule> = rule_lines
+            .iter()
+            .map(|rule_line| (rule_line.rule.symbol.to_string(), rule_line.rule.clone()))
+            .collect();
+        check_defined(&rules, &rule_lines)?;
+
+        let mut grammar = Grammar {
+            rules,
+            start_symbol: String::new(),
+        };
+        grammar.set_start(DEFAULT_START)?;
+        Ok(grammar)
+    }
+
+    pub fn set_start(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if !self.rules.contains_key(symbol) {
+            return Err(ParseError::input(
+                DAY,
+                format!("start rule {} is never defined", symbol),
+            ));
+        }
+        self.start_symbol = symbol.to_string();
+        Ok(())
+    }
+
+    /// Handle the `override` and `start` solver options.  Returns false for anything else.
+    pub fn apply_option(&mut self, name: &str, value: &str) -> Result<bool, ParseError> {
+        match name {
+            "override" => self.apply_overrides(value)?,
+            "start" => self.set_start(value.trim())?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Check the shape of the grammar.  Undefined rules are already errors when reading
+    /// it, so these are only the things that might not be what was meant.
+    pub fn validate(&self) -> Vec<GrammarWarning> {
+        let mut warnings = vec![];
+
+        let mut reachable = HashSet::new();
+        let mut to_visit = vec![self.start_symbol.as_str()];
+        while let Some(symbol) = to_visit.pop() {
+            if !reachable.insert(symbol) {
+                continue;
+            }
+            for sub_rule in &self.rules[symbol].sub_rules {
+                to_visit.extend(sub_rule.iter().map(|s| s.as_str()));
+            }
+        }
+        let unreachable = self.symbols_where(|symbol| !reachable.contains(symbol));
+        if !unreachable.is_empty() {
+            warnings.push(GrammarWarning::Unreachable(unreachable));
+        }
+
+        // a rule that only refers to itself (like 8 in part two) still counts as a root
+        let used_by_others: HashSet<&str> = self
+            .rules
+            .values()
+            .flat_map(|rule| {
+                rule.sub_rules
+                    .iter()
+                    .flatten()
+                    .filter(move |used| **used != rule.symbol)
+                    .map(|used| used.as_str())
+            })
+            .collect();
+        let roots = self.symbols_where(|symbol| !used_by_others.contains(symbol));
+        if roots.len() > 1 {
+            warnings.push(GrammarWarning::MultipleRoots(roots));
+        }
+
+        warnings
+    }
+
+    // sorted so that numbered rules come out in numeric order
+    fn symbols_where(&self, keep: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut symbols: Vec<String> = self
+            .rules
+            .keys()
+            .filter(|symbol| keep(symbol))
+            .cloned()
+            .collect();
+        symbols.sort_by(|a, b| (a.len(), a).cmp(&(b.len(), b)));
+        symbols
+    }
+
+    /// Replace rules with the ones in `text`, which uses the same `8: 42 | 42 8` format
+    /// as the puzzle input.  Rules that don't exist yet are added.
+    pub fn apply_overrides(&mut self, text: &str) -> Result<(), ParseError> {
+        let rule_lines = parse_rule_lines(text)?;
+        if rule_lines.is_empty() {
+            return Err(ParseError::input(
+                DAY,
+                format!("expected a rule like '8: 42 | 42 8', got '{}'", text),
+            ));
+        }
+        let mut rules = self.rules.clone();
+        for rule_line in &rule_lines {
+            rules.insert(rule_line.rule.symbol.to_string(), rule_line.rule.clone());
+        }
+        check_defined(&rules, &rule_lines)?;
+        self.rules = rules;
+        Ok(())
+    }
+}
+
+/// Split a puzzle input into its rules and its messages.  The rules come first, then a
+/// blank line, then one message per line.  Messages are kept exactly as written, since
+/// spaces could be part of a terminal.
+pub fn split_input(input: &str) -> (String, Vec<String>) {
+    let mut lines = input.lines();
+    let rules: Vec<&str> = lines
+        .by_ref()
+        .take_while(|line| !line.trim().is_empty())
+        .collect();
+    let messages = lines
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect();
+    (rules.join("\n"), messages)
+}
+
+fn parse_rule_lines(input: &str) -> Result<Vec<RuleLine<'_>>, ParseError> {
+    lazy_static! {
+        static ref RULE_RE: Regex = Regex::new(r"^(\w+): (.*)$").unwrap();
+    }
+    let mut rule_lines = vec![];
+    for (line_idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let captures = match RULE_RE.captures(line) {
+            Some(captures) => captures,
+            None => {
+                return Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    0,
+                    line,
+                    "expected a rule like '8: 42 | 42 8' or '4: \"a\"'",
+                ))
+            }
+        };
+        let leftside = captures.get(1).map_or("", |m| m.as_str()).to_string();
+        let rightside = captures.get(2).map_or("", |m| m.as_str());
+        let rightside_start = captures.get(2).map_or(0, |m| m.start());
+
+        if rightside.len() > 2
+            && rightside.starts_with('"')
+            && rightside.ends_with('"')
+            && !is_symbol_list(rightside)
+        {
+            // a terminal rule is everything between the outer quotes, so the literal can
+            // be any length and contain anything (even more quotes)
+            rule_lines.push(RuleLine {
+                rule: Rule {
+                    symbol: leftside,
+                    sub_rules: vec![],
+                    terminal: Some(rightside[1..rightside.len() - 1].to_string()),
+                },
+                line_idx,
+                line,
+                symbol_cols: vec![],
+            });
+            continue;
+        }
+
+        // not a terminal rule, so split it into alternatives, and those into symbols.  An
+        // alternative of just `""` matches nothing at all.
+        let mut all_rules = Rule {
+            symbol: leftside,
+            sub_rules: vec![],
+            terminal: None,
+        };
+        let mut symbol_cols = vec![];
+        let mut alternative_start = rightside_start;
+        for alternative in rightside.split('|') {
+            let error =
+                |col_idx, message| Err(ParseError::new(DAY, line_idx, col_idx, line, message));
+            let mut cur_rule = vec![];
+            let mut has_empty = false;
+            for (offset, symbol) in words(alternative) {
+                let col_idx = alternative_start + offset;
+                if symbol == "\"\"" {
+                    has_empty = true;
+                } else if symbol.starts_with('"') && (symbol.len() < 2 || !symbol.ends_with('"')) {
+                    return error(col_idx, "terminal is missing its closing quote");
+                } else if symbol.starts_with('"') {
+                    return error(
+                        col_idx,
+                        "terminals need a rule of their own, like '4: \"a\"'",
+                    );
+                } else {
+                    // remember rightside symbols
+                    symbol_cols.push((symbol.to_string(), col_idx));
+                    cur_rule.push(symbol.to_string());
+                }
+            }
+            if cur_rule.is_empty() && !has_empty {
+                return error(
+                    alternative_start,
+                    "empty alternative, use \"\" to match nothing",
+                );
+            }
+            all_rules.sub_rules.push(cur_rule);
+            alternative_start += alternative.len() + 1;
+        }
+
+        rule_lines.push(RuleLine {
+            rule: all_rules,
+            line_idx,
+            line,
+            symbol_cols,
+        });
+    }
+    Ok(rule_lines)
+}
+
+// whether `rightside` reads as symbols separated by spaces and `|`, where every symbol is
+// either a quoted terminal or a bare rule name, like `"a" | ""`.  Those lines aren't one
+// long literal, they're alternatives that try to use terminals inline.
+fn is_symbol_list(rightside: &str) -> bool {
+    let symbols: Vec<&str> = rightside
+        .split('|')
+        .flat_map(|alternative| words(alternative).into_iter().map(|(_, symbol)| symbol))
+        .collect();
+    symbols.len() > 1
+        && symbols.iter().all(|symbol| {
+            (symbol.len() >= 2 && symbol.starts_with('"') && symbol.ends_with('"'))
+                || !symbol.contains('"')
+        })
+}
+
+// the words in `text` that are separated by spaces, along with where each one starts
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = vec![];
+    let mut word_start = None;
+    for (idx, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (c == ' ', word_start) {
+            (true, Some(start)) => {
+                words.push((start, &text[start..idx]));
+                word_start = None;
+            }
+            (false, None) => word_start = Some(idx),
+            _ => (),
+        }
+    }
+    words
+}
+
+// every symbol used on a rightside has to have a rule, or parsing can't continue past it.
+// The error points at the first one, but names all of them.
+fn check_defined(rules: &HashMap<String, Rule>, rule_lines: &[RuleLine]) -> Result<(), ParseError> {
+    let mut undefined: Vec<(&RuleLine, &String, usize)> = vec![];
+    for rule_line in rule_lines {
+        for (symbol, col_idx) in &rule_line.symbol_cols {
+            if !rules.contains_key(symbol) && !undefined.iter().any(|(_, s, _)| *s == symbol) {
+                undefined.push((rule_line, symbol, *col_idx));
+            }
+        }
+    }
+    let (rule_line, symbol, col_idx) = match undefined.first() {
+        None => return Ok(()),
+        Some(first) => *first,
+    };
+    let message = match undefined.len() {
+        1 => format!("rule {} is never defined", symbol),
+        _ => {
+            let others: Vec<&str> = undefined[1..].iter().map(|(_, s, _)| s.as_str()).collect();
+            format!(
+                "rule {} is never defined (nor are {})",
+                symbol,
+                others.join(", ")
+            )
+        }
+    };
+    Err(ParseError::new(
+        DAY,
+        rule_line.line_idx,
+        col_idx,
+        rule_line.line,
+        message,
+    ))
+}
@@ -0,0 +1,490 @@
+use super::grammar::Grammar;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+/// The shortest and longest strings a rule can generate, in characters.  `max` is `None`
+/// when there is no longest, because the rule can grow forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthRange {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl fmt::Display for LengthRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) => write!(f, "{}..={}", self.min, max),
+            None => write!(f, "{}..", self.min),
+        }
+    }
+}
+
+/// Length ranges for every rule that generates at least one string.  Rules that can't
+/// (like `0: 0 1`) are left out.
+pub fn length_ranges(grammar: &Grammar) -> HashMap<String, LengthRange> {
+    let mins = min_lengths(grammar);
+    let unbounded = unbounded_rules(grammar, &mins);
+
+    // every rule is finite from here on, so the longest derivation never needs a rule more
+    // than once on the way down, and one round per rule is enough for it to settle
+    let mut maxes: HashMap<&str, usize> = HashMap::new();
+    for _ in 0..=grammar.rules.len() {
+        let mut changed = false;
+        for (symbol, rule) in &grammar.rules {
+            if !mins.contains_key(symbol.as_str()) || unbounded.contains(symbol.as_str()) {
+                continue;
+            }
+            let max = match &rule.terminal {
+                Some(literal) => Some(literal.chars().count()),
+                None => rule
+                    .sub_rules
+                    .iter()
+                    .filter_map(|sub_rule| {
+                        sub_rule
+                            .iter()
+                            .map(|used| maxes.get(used.as_str()))
+                            .sum::<Option<usize>>()
+                    })
+                    .max(),
+            };
+            if let Some(max) = max {
+                if maxes.get(symbol.as_str()) != Some(&max) {
+                    maxes.insert(symbol, max);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    mins.iter()
+        .map(|(symbol, min)| {
+            let range = LengthRange {
+                min: *min,
+                max: maxes.get(symbol).copied(),
+            };
+            (symbol.to_string(), range)
+        })
+        .collect()
+}
+
+// shortest string for each rule, keeping going until nothing gets shorter.  Rules that
+// never show up can't generate anything.
+fn min_lengths(grammar: &Grammar) -> HashMap<&str, usize> {
+    let mut mins: HashMap<&str, usize> = HashMap::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (symbol, rule) in &grammar.rules {
+            let min = match &rule.terminal {
+                Some(literal) => Some(literal.chars().count()),
+                None => rule
+                    .sub_rules
+                    .iter()
+                    .filter_map(|sub_rule| {
+                        sub_rule
+                            .iter()
+                            .map(|used| mins.get(used.as_str()))
+                            .sum::<Option<usize>>()
+                    })
+                    .min(),
+            };
+            if let Some(min) = min {
+                if mins.get(symbol.as_str()).is_none_or(|old| min < *old) {
+                    mins.insert(symbol, min);
+                    changed = true;
+                }
+            }
+        }
+    }
+    mins
+}
+
+// A rule can grow forever if it can reach a rule that derives itself with something
+// non-empty alongside, like `8: 42 | 42 8`.  Going round a loop that adds nothing (`1: 1 | 2`)
+// doesn't count.
+fn unbounded_rules<'a>(grammar: &'a Grammar, mins: &HashMap<&str, usize>) -> HashSet<&'a str> {
+    // only alternatives that can actually produce something matter
+    let usable =
+        |sub_rule: &Vec<String>| sub_rule.iter().all(|used| mins.contains_key(used.as_str()));
+
+    let mut non_empty: HashSet<&str> = HashSet::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (symbol, rule) in &grammar.rules {
+            let can_grow = match &rule.terminal {
+                Some(literal) => !literal.is_empty(),
+                None => rule.sub_rules.iter().filter(|s| usable(s)).any(|sub_rule| {
+                    sub_rule
+                        .iter()
+                        .any(|used| non_empty.contains(used.as_str()))
+                }),
+            };
+            if can_grow && non_empty.insert(symbol) {
+                changed = true;
+            }
+        }
+    }
+
+    // edges from a rule to the rules it uses, marked when the rest of the alternative
+    // can add something
+    let mut edges: HashMap<&str, Vec<(&str, bool)>> = HashMap::new();
+    for (symbol, rule) in &grammar.rules {
+        for sub_rule in rule.sub_rules.iter().filter(|s| usable(s)) {
+            for (idx, used) in sub_rule.iter().enumerate() {
+                let grows = sub_rule
+                    .iter()
+                    .enumerate()
+                    .any(|(other, s)| other != idx && non_empty.contains(s.as_str()));
+                edges
+                    .entry(symbol.as_str())
+                    .or_default()
+                    .push((used.as_str(), grows));
+            }
+        }
+    }
+    let reachable = |from: &'a str| -> HashSet<&'a str> {
+        let mut seen = HashSet::new();
+        let mut to_visit = vec![from];
+        while let Some(symbol) = to_visit.pop() {
+            if seen.insert(symbol) {
+                to_visit.extend(
+                    edges
+                        .get(symbol)
+                        .into_iter()
+                        .flatten()
+                        .map(|(used, _)| *used),
+                );
+            }
+        }
+        seen
+    };
+
+    let mut pumping: HashSet<&str> = HashSet::new();
+    for (symbol, symbol_edges) in &edges {
+        for (used, grows) in symbol_edges {
+            if *grows && reachable(used).contains(symbol) {
+                pumping.insert(symbol);
+            }
+        }
+    }
+    grammar
+        .rules
+        .keys()
+        .map(|symbol| symbol.as_str())
+        .filter(|symbol| mins.contains_key(symbol))
+        .filter(|symbol| reachable(symbol).iter().any(|s| pumping.contains(s)))
+        .collect()
+}
+
+/// Every distinct string `symbol` generates, shortest first and in order within each
+/// length, up to `max_length` characters if given.  Without a limit, an unbounded rule
+/// never runs out.
+pub fn enumerate<'a>(grammar: &'a Grammar, symbol: &str, max_length: Option<usize>) -> Strings<'a> {
+    let ranges = length_ranges(grammar);
+    let (length, last_length) = match ranges.get(symbol) {
+        // nothing to generate, so start past the end
+        None => (1, Some(0)),
+        Some(range) => {
+            let last = match (range.max, max_length) {
+                (Some(max), Some(limit)) => Some(max.min(limit)),
+                (max, limit) => max.or(limit),
+            };
+            (range.min, last)
+        }
+    };
+    Strings {
+        generator: Generator {
+            grammar,
+            ranges,
+            strings: HashMap::new(),
+            sequences: HashMap::new(),
+            reachable: HashMap::new(),
+            working: HashMap::new(),
+        },
+        symbol: symbol.to_string(),
+        length,
+        last_length,
+        current: None,
+    }
+}
+
+/// How many distinct strings `symbol` generates (up to `max_length` characters, if given).
+/// `None` if there are infinitely many.  This counts by enumerating them, so it takes as
+/// long as listing them would.
+pub fn count(grammar: &Grammar, symbol: &str, max_length: Option<usize>) -> Option<usize> {
+    let range = match length_ranges(grammar).get(symbol) {
+        None => return Some(0),
+        Some(range) => *range,
+    };
+    if range.max.is_none() && max_length.is_none() {
+        return None;
+    }
+    Some(enumerate(grammar, symbol, max_length).count())
+}
+
+// the strings of an exact length for each rule (and each tail of an alternative), sorted
+// and without repeats, kept around since the same ones get asked for over and over
+struct Generator<'a> {
+    grammar: &'a Grammar,
+    ranges: HashMap<String, LengthRange>,
+    strings: HashMap<(String, usize), Rc<Vec<String>>>,
+    sequences: HashMap<(Vec<String>, usize), Rc<Vec<String>>>,
+    reachable: HashMap<String, Rc<Vec<String>>>,
+    // the lists for lengths still being worked out, which aren't finished yet
+    working: HashMap<usize, HashMap<String, Rc<Vec<String>>>>,
+}
+
+impl<'a> Generator<'a> {
+    fn fits(&self, symbol: &str, length: usize) -> bool {
+        match self.ranges.get(symbol) {
+            None => false,
+            Some(range) => length >= range.min && range.max.is_none_or(|max| length <= max),
+        }
+    }
+
+    /// Sorted lists that merge into all strings of `symbol` with exactly `length` characters
+    fn sources(&mut self, symbol: &str, length: usize) -> Vec<Product> {
+        let rule = &self.grammar.rules[symbol];
+        if let Some(literal) = &rule.terminal {
+            if literal.chars().count() != length {
+                return vec![];
+            }
+            return vec![Product::single(literal.to_string())];
+        }
+        let mut sources = vec![];
+        for sub_rule in &rule.sub_rules {
+            let (first, rest) = match sub_rule.split_first() {
+                None if length == 0 => {
+                    sources.push(Product::single(String::new()));
+                    continue;
+                }
+                None => continue,
+                Some(split) => split,
+            };
+            let rest_min: usize = rest
+                .iter()
+                .map(|used| self.ranges.get(used).map_or(usize::MAX / 2, |r| r.min))
+                .sum();
+            for first_length in 0..=length.saturating_sub(rest_min) {
+                if !self.fits(first, first_length) {
+                    continue;
+                }
+                let heads = self.strings(first, first_length);
+                if heads.is_empty() {
+                    continue;
+                }
+                let tails = self.sequence(rest, length - first_length);
+                if !tails.is_empty() {
+                    sources.push(Product::new(heads, tails));
+                }
+            }
+        }
+        sources
+    }
+
+    fn strings(&mut self, symbol: &str, length: usize) -> Rc<Vec<String>> {
+        let key = (symbol.to_string(), length);
+        if let Some(strings) = self.strings.get(&key) {
+            return strings.clone();
+        }
+        if let Some(working) = self.working.get(&length) {
+            return working.get(symbol).cloned().unwrap_or_default();
+        }
+
+        // Rules can use each other without using up any length (`1: 2 | ""`, `2: 1 3`), so
+        // at one length everything this rule leads to is worked out together, going round
+        // until nothing new turns up.  Lists only ever grow, and only so far, so it stops.
+        let group = self.reachable(symbol);
+        let empty = Rc::new(vec![]);
+        let working = group
+            .iter()
+            .map(|s| (s.to_string(), empty.clone()))
+            .collect();
+        self.working.insert(length, working);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for member in group.iter() {
+                if !self.fits(member, length) {
+                    continue;
+                }
+                let strings: Vec<String> = Merge::new(self.sources(member, length)).collect();
+                let working = self.working.get_mut(&length).unwrap();
+                if *working[member] != strings {
+                    working.insert(member.to_string(), Rc::new(strings));
+                    changed = true;
+                }
+            }
+        }
+        for (member, strings) in self.working.remove(&length).unwrap() {
+            self.strings.insert((member, length), strings);
+        }
+        self.strings[&key].clone()
+    }
+
+    fn reachable(&mut self, symbol: &str) -> Rc<Vec<String>> {
+        if let Some(reachable) = self.reachable.get(symbol) {
+            return reachable.clone();
+        }
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut to_visit = vec![symbol];
+        while let Some(next) = to_visit.pop() {
+            if seen.insert(next) {
+                let rule = &self.grammar.rules[next];
+                to_visit.extend(rule.sub_rules.iter().flatten().map(|used| used.as_str()));
+            }
+        }
+        let reachable: Rc<Vec<String>> = Rc::new(seen.iter().map(|s| s.to_string()).collect());
+        self.reachable.insert(symbol.to_string(), reachable.clone());
+        reachable
+    }
+
+    fn sequence(&mut self, symbols: &[String], length: usize) -> Rc<Vec<String>> {
+        let (first, rest) = match symbols.split_first() {
+            None if length == 0 => return Rc::new(vec![String::new()]),
+            None => return Rc::new(vec![]),
+            Some(split) => split,
+        };
+        let key = (symbols.to_vec(), length);
+        if let Some(strings) = self.sequences.get(&key) {
+            return strings.clone();
+        }
+        let rest_min: usize = rest
+            .iter()
+            .map(|used| self.ranges.get(used).map_or(usize::MAX / 2, |r| r.min))
+            .sum();
+        let mut sources = vec![];
+        for first_length in 0..=length.saturating_sub(rest_min) {
+            if !self.fits(first, first_length) {
+                continue;
+            }
+            let heads = self.strings(first, first_length);
+            let tails = self.sequence(rest, length - first_length);
+            if !heads.is_empty() && !tails.is_empty() {
+                sources.push(Product::new(heads, tails));
+            }
+        }
+        let strings: Rc<Vec<String>> = Rc::new(Merge::new(sources).collect());
+        // a list built from one still being worked out may be missing some
+        if !self.working.keys().any(|working| *working <= length) {
+            self.sequences.insert(key, strings.clone());
+        }
+        strings
+    }
+}
+
+/// Every head followed by every tail.  All the heads are the same length, so this comes
+/// out sorted as long as both lists are.
+struct Product {
+    heads: Rc<Vec<String>>,
+    tails: Rc<Vec<String>>,
+    head: usize,
+    tail: usize,
+}
+
+impl Product {
+    fn new(heads: Rc<Vec<String>>, tails: Rc<Vec<String>>) -> Product {
+        Product {
+            heads,
+            tails,
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    fn single(value: String) -> Product {
+        Product::new(Rc::new(vec![value]), Rc::new(vec![String::new()]))
+    }
+}
+
+impl Iterator for Product {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.tail >= self.tails.len() {
+            self.tail = 0;
+            self.head += 1;
+        }
+        let head = self.heads.get(self.head)?;
+        let value = format!("{}{}", head, self.tails[self.tail]);
+        self.tail += 1;
+        Some(value)
+    }
+}
+
+/// Merges sorted sources into one sorted stream, dropping repeats (which is how an
+/// ambiguous grammar only counts each string once)
+struct Merge {
+    sources: Vec<Product>,
+    heap: BinaryHeap<Reverse<(String, usize)>>,
+    last: Option<String>,
+}
+
+impl Merge {
+    fn new(mut sources: Vec<Product>) -> Merge {
+        let mut heap = BinaryHeap::new();
+        for (idx, source) in sources.iter_mut().enumerate() {
+            if let Some(value) = source.next() {
+                heap.push(Reverse((value, idx)));
+            }
+        }
+        Merge {
+            sources,
+            heap,
+            last: None,
+        }
+    }
+}
+
+impl Iterator for Merge {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some(Reverse((value, idx))) = self.heap.pop() {
+            if let Some(next) = self.sources[idx].next() {
+                self.heap.push(Reverse((next, idx)));
+            }
+            if self.last.as_ref() != Some(&value) {
+                self.last = Some(value.clone());
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// The lazy iterator behind `enumerate`.  Only one length is worked on at a time.
+pub struct Strings<'a> {
+    generator: Generator<'a>,
+    symbol: String,
+    length: usize,
+    last_length: Option<usize>,
+    current: Option<Merge>,
+}
+
+impl<'a> Iterator for Strings<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(value) = self.current.as_mut().and_then(|merge| merge.next()) {
+                return Some(value);
+            }
+            if self.current.is_some() {
+                self.length += 1;
+            }
+            if self.last_length.is_some_and(|last| self.length > last) {
+                return None;
+            }
+            // the top level is streamed instead of kept, since it is usually the biggest list
+            let sources = self.generator.sources(&self.symbol, self.length);
+            self.current = Some(Merge::new(sources));
+        }
+    }
+}
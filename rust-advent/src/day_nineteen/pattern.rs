@@ -0,0 +1,93 @@
+use super::grammar::Grammar;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+
+// every use of a rule repeats its whole pattern, so grammars that nest deeply can grow
+// past what the regex crate allows by default
+const SIZE_LIMIT: usize = 64 * 1024 * 1024;
+// patterns much longer than this take longer to compile than Earley takes to parse, and
+// deep grammars would use up all memory just spelling theirs out
+const PATTERN_LIMIT: usize = 1024 * 1024;
+
+/// True if some rule reachable from the start symbol refers back to itself, directly or
+/// through other rules.  Those grammars can match unbounded nesting, so no regex can do.
+pub fn is_recursive(grammar: &Grammar) -> bool {
+    // 0: not seen yet, 1: on the current path, 2: done and known not to loop
+    fn visit<'a>(grammar: &'a Grammar, symbol: &'a str, state: &mut HashMap<&'a str, u8>) -> bool {
+        match state.get(symbol) {
+            Some(1) => return true,
+            Some(2) => return false,
+            _ => (),
+        }
+        state.insert(symbol, 1);
+        for sub_rule in &grammar.rules[symbol].sub_rules {
+            for used in sub_rule {
+                if visit(grammar, used, state) {
+                    return true;
+                }
+            }
+        }
+        state.insert(symbol, 2);
+        false
+    }
+    visit(grammar, &grammar.start_symbol, &mut HashMap::new())
+}
+
+/// The whole grammar as one anchored regex, or `None` if it is recursive or the
+/// pattern is too big to build
+pub fn compile(grammar: &Grammar) -> Option<Regex> {
+    if is_recursive(grammar) {
+        return None;
+    }
+    let mut patterns = HashMap::new();
+    let pattern = match symbol_pattern(grammar, &grammar.start_symbol, &mut patterns) {
+        Some(pattern) => format!("^{}$", pattern),
+        None => {
+            info!("the grammar regex would be over {} bytes", PATTERN_LIMIT);
+            return None;
+        }
+    };
+    debug!("grammar regex is {} bytes", pattern.len());
+    RegexBuilder::new(&pattern)
+        .size_limit(SIZE_LIMIT)
+        .build()
+        .map_err(|err| info!("could not compile the grammar to a regex: {}", err))
+        .ok()
+}
+
+// rules get used many times over, so remember the pattern for each one once it is built.
+// Each use still copies the whole pattern, so give up with `None` as soon as one gets
+// longer than `PATTERN_LIMIT`, rather than building it out.
+fn symbol_pattern<'a>(
+    grammar: &'a Grammar,
+    symbol: &'a str,
+    patterns: &mut HashMap<&'a str, String>,
+) -> Option<String> {
+    if let Some(pattern) = patterns.get(symbol) {
+        return Some(pattern.to_string());
+    }
+    let rule = &grammar.rules[symbol];
+    let pattern = match &rule.terminal {
+        Some(literal) => regex::escape(literal),
+        None => {
+            let mut alternatives = vec![];
+            let mut len = 0;
+            for sub_rule in &rule.sub_rules {
+                let mut alternative = String::new();
+                for used in sub_rule {
+                    let used_pattern = symbol_pattern(grammar, used, patterns)?;
+                    len += used_pattern.len();
+                    if len > PATTERN_LIMIT {
+                        return None;
+                    }
+                    alternative.push_str(&used_pattern);
+                }
+                alternatives.push(alternative);
+            }
+            // an empty alternative becomes an empty branch, which matches nothing as it should
+            format!("(?:{})", alternatives.join("|"))
+        }
+    };
+    patterns.insert(symbol, pattern.to_string());
+    Some(pattern)
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+
+/// One derivation of (part of) a message.  Nodes remember which alternative of their
+/// rule matched, so ambiguous grammars can be told apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTree {
+    Leaf {
+        symbol: String,
+        literal: String,
+    },
+    Node {
+        symbol: String,
+        alternative: usize, // index into the rule's alternatives
+        rhs: Vec<String>,
+        children: Vec<ParseTree>,
+    },
+}
+
+impl ParseTree {
+    pub fn symbol(&self) -> &str {
+        match self {
+            ParseTree::Leaf { symbol, .. } => symbol,
+            ParseTree::Node { symbol, .. } => symbol,
+        }
+    }
+
+    /// The part of the message this tree covers
+    pub fn text(&self) -> String {
+        match self {
+            ParseTree::Leaf { literal, .. } => literal.to_string(),
+            ParseTree::Node { children, .. } => children.iter().map(|c| c.text()).collect(),
+        }
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        match self {
+            ParseTree::Leaf { symbol, literal } => {
+                writeln!(f, "{}{} -> {:?}", indent, symbol, literal)
+            }
+            ParseTree::Node {
+                symbol,
+                alternative,
+                rhs,
+                children,
+            } => {
+                // an empty alternative is written as "" in the rules, so show it that way too
+                let rhs = if rhs.is_empty() {
+                    String::from("\"\"")
+                } else {
+                    rhs.join(" ")
+                };
+                writeln!(
+                    f,
+                    "{}{} -> {}  (alternative {})",
+                    indent,
+                    symbol,
+                    rhs,
+                    alternative + 1
+                )?;
+                for child in children {
+                    child.write_indented(f, depth + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Graphviz source for this tree, e.g. for `dot -Tsvg`
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut out = format!("digraph {} {{\n", dot_id(name));
+        let mut next_id = 0;
+        self.write_dot(&mut out, &mut next_id);
+        out.push_str("}\n");
+        out
+    }
+
+    // returns the id of the node that was written, so the parent can link to it
+    fn write_dot(&self, out: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        match self {
+            ParseTree::Leaf { symbol, literal } => {
+                out.push_str(&format!(
+                    "  n{} [label={}];\n  n{} [label={}, shape=box];\n  n{} -> n{};\n",
+                    id,
+                    dot_id(symbol),
+                    id + 1,
+                    dot_id(literal),
+                    id,
+                    id + 1
+                ));
+                *next_id += 1;
+            }
+            ParseTree::Node {
+                symbol,
+                alternative,
+                children,
+                ..
+            } => {
+                out.push_str(&format!(
+                    "  n{} [label={}];\n",
+                    id,
+                    dot_id(&format!("{} (alt {})", symbol, alternative + 1))
+                ));
+                for child in children {
+                    let child_id = child.write_dot(out, next_id);
+                    out.push_str(&format!("  n{} -> n{};\n", id, child_id));
+                }
+            }
+        }
+        id
+    }
+}
+
+impl fmt::Display for ParseTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+// DOT ids can be any double quoted string, as long as quotes and backslashes are escaped
+fn dot_id(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
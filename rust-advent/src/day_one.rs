@@ -1,36 +1,48 @@
-use super::Solver;
+use super::{Answer, ParseError, SolveError, Solver};
+
+const DAY: usize = 1;
 
 pub struct DayOneSolver {
     all_ints: Vec<usize>,
 }
 
 impl Solver for DayOneSolver {
-    fn from_input(input: &String) -> Result<Box<DayOneSolver>, String> {
+    fn from_input(input: &str) -> Result<Box<DayOneSolver>, ParseError> {
         let mut all_ints: Vec<usize> = Vec::new();
 
-        for line in input.lines() {
+        for (line_idx, line) in input.lines().enumerate() {
             all_ints.push(match line.parse() {
                 Ok(val) => val,
-                Err(error) => return Err(error.to_string()),
+                Err(error) => {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        0,
+                        line,
+                        format!("expected an expense amount: {}", error),
+                    ))
+                }
             });
         }
 
         Ok(Box::new(DayOneSolver { all_ints: all_ints }))
     }
-    fn part_one(&self) -> Result<usize, &str> {
+    fn part_one(&self) -> Result<Answer, SolveError> {
         for i in 0..self.all_ints.len() {
             let i_val = self.all_ints[i];
             for j in i..self.all_ints.len() {
                 let j_val = self.all_ints[j];
                 if i_val + j_val == 2020 {
-                    return Ok(i_val * j_val);
+                    return Ok(Answer::Int(i_val * j_val));
                 }
             }
         }
-        Err("No solution found for part one")
+        Err(SolveError::NoSolution(String::from(
+            "no two entries sum to 2020",
+        )))
     }
 
-    fn part_two(&self) -> Result<usize, &str> {
+    fn part_two(&self) -> Result<Answer, SolveError> {
         let len = self.all_ints.len();
         for i in 0..len {
             let i_val = self.all_ints[i];
@@ -39,11 +51,13 @@ impl Solver for DayOneSolver {
                 for k in j..len {
                     let k_val = self.all_ints[k];
                     if i_val + j_val + k_val == 2020 {
-                        return Ok(i_val * j_val * k_val);
+                        return Ok(Answer::Int(i_val * j_val * k_val));
                     }
                 }
             }
         }
-        Err("No solution found for part two")
+        Err(SolveError::NoSolution(String::from(
+            "no three entries sum to 2020",
+        )))
     }
 }
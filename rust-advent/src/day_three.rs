@@ -1,4 +1,6 @@
-use super::Solver;
+use super::{Answer, ParseError, SolveError, Solver};
+
+const DAY: usize = 3;
 
 pub struct DayThreeSolver {
     lines: Vec<String>,
@@ -26,18 +28,52 @@ impl DayThreeSolver {
 }
 
 impl Solver for DayThreeSolver {
-    fn from_input(input: &String) -> Result<Box<DayThreeSolver>, String> {
+    fn from_input(input: &str) -> Result<Box<DayThreeSolver>, ParseError> {
         let all_lines: Vec<String> = input.lines().map(|l| l.to_string()).collect();
+        if all_lines.is_empty() {
+            return Err(ParseError::input(DAY, "the map is empty"));
+        }
+        let width = all_lines[0].len();
+        if width == 0 {
+            return Err(ParseError::new(
+                DAY,
+                0,
+                0,
+                &all_lines[0],
+                "the map has no columns",
+            ));
+        }
+        for (line_idx, line) in all_lines.iter().enumerate() {
+            if let Some(col_idx) = line.chars().position(|c| c != '.' && c != '#') {
+                return Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    col_idx,
+                    line,
+                    "expected '.' or '#'",
+                ));
+            }
+            if line.len() != width {
+                return Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    line.len().min(width),
+                    line,
+                    format!("every row should be {} wide", width),
+                ));
+            }
+        }
         Ok(Box::new(DayThreeSolver { lines: all_lines }))
     }
-    fn part_one(&self) -> Result<usize, &str> {
-        Ok(self.tree_encounters(3, 1))
+    fn part_one(&self) -> Result<Answer, SolveError> {
+        Ok(Answer::Int(self.tree_encounters(3, 1)))
     }
-    fn part_two(&self) -> Result<usize, &str> {
+    fn part_two(&self) -> Result<Answer, SolveError> {
         let slopes = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
-        Ok(slopes
+        let product = slopes
             .iter()
             .map(|slope| self.tree_encounters(slope.0, slope.1))
-            .product::<usize>())
+            .product::<usize>();
+        Ok(Answer::Int(product))
     }
 }
@@ -1,74 +1,226 @@
-mod graph;
-mod tiles;
+pub mod assembly;
+pub mod image;
+pub mod pattern;
+pub mod tiles;
 
-use super::Solver;
-use crate::day_twenty::graph::TileGraph;
+use super::{Answer, ParseError, SolveError, Solver};
+use crate::day_twenty::assembly::Layout;
+use crate::day_twenty::pattern::Pattern;
 use crate::day_twenty::tiles::*;
 use regex::Regex;
 use std::collections::HashMap;
 
+const DAY: usize = 20;
+
+// the puzzle's tiles have a one cell border that only exists to line them up
+const DEFAULT_BORDER: usize = 1;
+
 pub struct DayTwentySolver {
     tiles: HashMap<usize, Tile>,
+    size: usize,                    // cells along each side of every tile
+    border: usize,                  // cells stripped from each edge of a tile when stitching
+    layout: Option<(usize, usize)>, // tiles across and down, if known up front
+    pattern: Pattern,               // what part two looks for, the sea monster unless set
+}
+
+impl DayTwentySolver {
+    /// Put the tiles together, using the `layout` option if it was given
+    pub fn assemble(&self) -> Result<Layout, SolveError> {
+        // sorted, so ties between equally good layouts always go the same way
+        let mut tiles: Vec<Tile> = self.tiles.values().cloned().collect();
+        tiles.sort_by_key(|tile| tile.id);
+        let layout = assembly::assemble(&tiles, self.layout)
+            .map_err(|err| SolveError::NoSolution(err.to_string()))?;
+        debug!("{}x{} layout:\n{}", layout.width, layout.height, layout);
+        Ok(layout)
+    }
+
+    /// How many cells of each tile edge part two strips, set with the `border` option
+    pub fn border(&self) -> usize {
+        self.border
+    }
+}
+
+// "12x9" is 12 tiles across and 9 down
+fn parse_layout(value: &str) -> Option<(usize, usize)> {
+    let (width, height) = value.split_once('x')?;
+    match (width.trim().parse(), height.trim().parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Some((width, height)),
+        _ => None,
+    }
 }
 
 impl Solver for DayTwentySolver {
-    fn from_input(input: &String) -> Result<Box<DayTwentySolver>, String> {
+    fn from_input(input: &str) -> Result<Box<DayTwentySolver>, ParseError> {
         lazy_static! {
             static ref TILE_NAME_RE: Regex = Regex::new(r"Tile (\d*):").unwrap();
             static ref TILE_LINE_RE: Regex = Regex::new(r"^[.#]+$").unwrap();
         }
         let mut tile_map = HashMap::new();
         let mut cur_id = 0;
-        let mut cur_arr = [[false; 10]; 10];
-        let mut tile_row = 0;
-        for line in input.lines() {
+        let mut cur_rows: Vec<Vec<bool>> = vec![];
+        // every tile has to be the size of the first row of the first tile
+        let mut size = 0;
+        let mut last_line = (0, "");
+        // a tile is only complete once all of its rows have been read
+        let finish_tile = |id: usize, rows: usize, size: usize, (line_idx, line): (usize, &str)| {
+            if rows == 0 {
+                Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    line.len(),
+                    line,
+                    format!("tile {} has no rows", id),
+                ))
+            } else if rows == size {
+                Ok(())
+            } else {
+                Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    line.len(),
+                    line,
+                    format!("tile {} has {} rows, expected {}", id, rows, size),
+                ))
+            }
+        };
+        for (line_idx, line) in input.lines().enumerate() {
             if TILE_LINE_RE.is_match(line) {
-                for (idx, c) in line.chars().enumerate() {
-                    cur_arr[tile_row][idx] = c == '#';
+                if cur_id == 0 {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        0,
+                        line,
+                        "tile row found before any 'Tile <id>:' header",
+                    ));
                 }
-                tile_row += 1;
-            } else {
-                match TILE_NAME_RE.captures(line) {
-                    Some(captures) => {
-                        // add the previous tile to the map
-                        if cur_id != 0 {
-                            tile_map.insert(cur_id, Tile::new(cur_id, cur_arr));
-                        }
-                        cur_arr = [[false; 10]; 10];
-                        tile_row = 0;
+                if size == 0 {
+                    size = line.len();
+                }
+                if line.len() != size || cur_rows.len() >= size {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        line.len().min(size),
+                        line,
+                        format!("tile {} should be {} by {}", cur_id, size, size),
+                    ));
+                }
+                cur_rows.push(line.chars().map(|c| c == '#').collect());
+                last_line = (line_idx, line);
+            } else if let Some(captures) = TILE_NAME_RE.captures(line) {
+                // add the previous tile to the map
+                if cur_id != 0 {
+                    finish_tile(cur_id, cur_rows.len(), size, last_line)?;
+                    tile_map.insert(cur_id, Tile::new(cur_id, cur_rows));
+                }
+                cur_rows = vec![];
+                last_line = (line_idx, line);
 
-                        // get the new id
-                        let id = captures.get(1).map_or("", |m| m.as_str()).to_string();
-                        cur_id = id.parse().unwrap();
+                // get the new id
+                let id = captures.get(1).unwrap();
+                cur_id = match id.as_str().parse() {
+                    Ok(id) if id != 0 => id,
+                    _ => {
+                        return Err(ParseError::new(
+                            DAY,
+                            line_idx,
+                            id.start(),
+                            line,
+                            "expected a non-zero tile id",
+                        ))
                     }
-                    None => (),
-                }
+                };
+            } else if !line.trim().is_empty() {
+                return Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    0,
+                    line,
+                    "expected 'Tile <id>:' or a row of '.' and '#'",
+                ));
             }
         }
+        if cur_id == 0 {
+            return Err(ParseError::input(DAY, "no tiles found"));
+        }
         // I always forget to add the last one...
-        tile_map.insert(cur_id, Tile::new(cur_id, cur_arr));
+        finish_tile(cur_id, cur_rows.len(), size, last_line)?;
+        if size <= 2 * DEFAULT_BORDER {
+            return Err(ParseError::input(
+                DAY,
+                format!(
+                    "tiles are {} by {}, too small to strip their borders",
+                    size, size
+                ),
+            ));
+        }
+        tile_map.insert(cur_id, Tile::new(cur_id, cur_rows));
 
-        Ok(Box::new(DayTwentySolver { tiles: tile_map }))
+        Ok(Box::new(DayTwentySolver {
+            tiles: tile_map,
+            size,
+            border: DEFAULT_BORDER,
+            layout: None,
+            pattern: Pattern::sea_monster(),
+        }))
     }
 
-    fn part_one(&self) -> Result<usize, &str> {
-        let graph = TileGraph::from(self.tiles.clone());
-
-        // If there were only 2 matches, this HAS to be a corner
-        let corners = graph.by_count.get(&2).unwrap();
-        println!("{:?}", corners);
-        // The inputs that we were given do not result in more than 4 corners, hopefully
-        if corners.len() != 4 {
-            panic!("This problem is harder than expected!");
+    fn set_option(&mut self, name: &str, value: &str) -> Result<bool, ParseError> {
+        if name == "layout" {
+            self.layout = match parse_layout(value) {
+                Some(layout) => Some(layout),
+                None => {
+                    return Err(ParseError::input(
+                        DAY,
+                        format!("layout should look like 12x9, got '{}'", value),
+                    ))
+                }
+            };
+            return Ok(true);
+        }
+        if name == "pattern" {
+            // a file with the pattern drawn in '#'
+            let text = std::fs::read_to_string(value).map_err(|err| {
+                ParseError::input(DAY, format!("could not read pattern {}: {}", value, err))
+            })?;
+            self.pattern = Pattern::parse(&text)?;
+            return Ok(true);
+        }
+        if name != "border" {
+            return Ok(false);
         }
+        // something has to be left of each tile once both borders are gone
+        self.border = match value.parse() {
+            Ok(border) if 2 * border < self.size => border,
+            _ => {
+                return Err(ParseError::input(
+                    DAY,
+                    format!(
+                        "border must be a number less than half the tile size ({}), got '{}'",
+                        self.size, value
+                    ),
+                ))
+            }
+        };
+        Ok(true)
+    }
+
+    fn part_one(&self) -> Result<Answer, SolveError> {
+        let corners = self.assemble()?.corners();
+        debug!("corners: {:?}", corners);
         let product: usize = corners.iter().fold(1, |acc, id| acc * id);
-        Ok(product)
+        Ok(Answer::Int(product))
     }
-    fn part_two(&self) -> Result<usize, &str> {
-        // make graph of links
-        let graph = TileGraph::from(self.tiles.clone());
+    fn part_two(&self) -> Result<Answer, SolveError> {
+        let layout = self.assemble()?;
 
         // stitch tiles, find monsters and total non-monster '#'
-        Ok(graph.check_for_monsters())
+        let image = layout.stitch(self.border);
+        image::trace_image(&image, self.size - 2 * self.border);
+        let matches = self.pattern.find(&image);
+        debug!("found the pattern {} times", matches.len());
+        Ok(Answer::Int(pattern::roughness(&image, &matches)))
     }
 }
@@ -0,0 +1,354 @@
+use super::tiles::{Orientation, SideVals, Tile};
+use std::collections::HashMap;
+use std::fmt;
+
+// sides in the order tiles keep them
+const NORTH: usize = 0;
+const EAST: usize = 1;
+const SOUTH: usize = 2;
+const WEST: usize = 3;
+
+/// Why the tiles couldn't be put together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssemblyError {
+    NoTiles,
+    /// A layout was asked for that needs more tiles than there are
+    TooFewTiles {
+        needed: usize,
+        found: usize,
+    },
+    /// Nothing fits, either in the layout asked for or (if none was) in any layout at
+    /// least 2 by 2
+    NoArrangement {
+        layout: Option<(usize, usize)>,
+        tiles: usize,
+    },
+}
+
+impl fmt::Display for AssemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssemblyError::NoTiles => write!(f, "there are no tiles to assemble"),
+            AssemblyError::TooFewTiles { needed, found } => write!(
+                f,
+                "the layout needs {} tiles but there are only {}",
+                needed, found
+            ),
+            AssemblyError::NoArrangement {
+                layout: Some((width, height)),
+                tiles,
+            } => write!(
+                f,
+                "no {}x{} arrangement of the {} tiles fits together",
+                width, height, tiles
+            ),
+            AssemblyError::NoArrangement {
+                layout: None,
+                tiles,
+            } => write!(
+                f,
+                "no arrangement of the {} tiles fits together, not even 2x2",
+                tiles
+            ),
+        }
+    }
+}
+
+/// The tiles laid out in rows, each turned the right way round
+#[derive(Clone)]
+pub struct Layout {
+    pub width: usize,
+    pub height: usize,
+    pub tiles: Vec<Vec<Tile>>,
+}
+
+impl Layout {
+    /// Ids of the corner tiles, clockwise from the top left
+    pub fn corners(&self) -> [usize; 4] {
+        let last_row = &self.tiles[self.height - 1];
+        [
+            self.tiles[0][0].id,
+            self.tiles[0][self.width - 1].id,
+            last_row[self.width - 1].id,
+            last_row[0].id,
+        ]
+    }
+
+    /// Put the tiles together into one image, leaving out `border` cells from each edge of
+    /// every tile
+    pub fn stitch(&self, border: usize) -> Vec<Vec<bool>> {
+        let tile_size = self.tiles[0][0].size();
+        let mut image = vec![];
+        for tile_row in &self.tiles {
+            for row_idx in border..tile_size - border {
+                image.push(
+                    tile_row
+                        .iter()
+                        .flat_map(|tile| &tile.data[row_idx][border..tile_size - border])
+                        .copied()
+                        .collect(),
+                );
+            }
+        }
+        image
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.tiles {
+            let ids: Vec<String> = row.iter().map(|tile| tile.id.to_string()).collect();
+            writeln!(f, "{}", ids.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Put the tiles together into a `width` by `height` grid where every pair of neighbours
+/// share an edge.  Without a layout, the largest one that works is used, most square
+/// first, down to 2 by 2; leftover tiles are decoys.  Edges can be shared by any number
+/// of tiles, the search backs up and tries again whenever it gets stuck.
+pub fn assemble(tiles: &[Tile], layout: Option<(usize, usize)>) -> Result<Layout, AssemblyError> {
+    if tiles.is_empty() {
+        return Err(AssemblyError::NoTiles);
+    }
+    let search = Search::new(tiles);
+    if let Some((width, height)) = layout {
+        if width * height > tiles.len() {
+            return Err(AssemblyError::TooFewTiles {
+                needed: width * height,
+                found: tiles.len(),
+            });
+        }
+        return search
+            .run(width, height)
+            .ok_or(AssemblyError::NoArrangement {
+                layout,
+                tiles: tiles.len(),
+            });
+    }
+
+    // when every edge is shared by just two tiles, the counts of corner, edge and inner
+    // tiles give the layout away
+    if let Some((width, height)) = search.derived_layout() {
+        debug!("edge counts point to a {}x{} layout", width, height);
+        if let Some(layout) = search.run(width, height) {
+            return Ok(layout);
+        }
+    }
+
+    // the whole thing can be turned, so a layout only needs trying one way round
+    for area in (4..=tiles.len()).rev() {
+        let mut layouts: Vec<(usize, usize)> = (2..)
+            .take_while(|height| height * height <= area)
+            .filter(|height| area % height == 0)
+            .map(|height| (area / height, height))
+            .collect();
+        layouts.reverse();
+        for (width, height) in layouts {
+            if !search.could_fit(width, height) {
+                continue;
+            }
+            debug!("trying a {}x{} layout", width, height);
+            if let Some(layout) = search.run(width, height) {
+                return Ok(layout);
+            }
+        }
+    }
+    Err(AssemblyError::NoArrangement {
+        layout: None,
+        tiles: tiles.len(),
+    })
+}
+
+struct Search<'a> {
+    tiles: &'a [Tile],
+    // every tile's sides in each of its orientations, by tile then `Orientation::ALL` index
+    sides: Vec<Vec<[SideVals; 4]>>,
+    // which (tile, orientation) pairs have a given west or north side
+    by_west: HashMap<SideVals, Vec<(usize, usize)>>,
+    by_north: HashMap<SideVals, Vec<(usize, usize)>>,
+    // how many of each tile's sides some other tile could sit against
+    shared_edges: Vec<usize>,
+    // tiles sharing the fewest edges first, since those are the likeliest corners
+    starts: Vec<usize>,
+}
+
+impl<'a> Search<'a> {
+    fn new(tiles: &'a [Tile]) -> Search<'a> {
+        let sides: Vec<Vec<[SideVals; 4]>> = tiles
+            .iter()
+            .map(|tile| {
+                Orientation::ALL
+                    .iter()
+                    .map(|orientation| orientation.apply_to_sides(tile.sides()))
+                    .collect()
+            })
+            .collect();
+        let mut by_west: HashMap<SideVals, Vec<(usize, usize)>> = HashMap::new();
+        let mut by_north: HashMap<SideVals, Vec<(usize, usize)>> = HashMap::new();
+        for (tile_idx, oriented) in sides.iter().enumerate() {
+            for (orientation_idx, tile_sides) in oriented.iter().enumerate() {
+                by_west
+                    .entry(tile_sides[WEST].clone())
+                    .or_default()
+                    .push((tile_idx, orientation_idx));
+                by_north
+                    .entry(tile_sides[NORTH].clone())
+                    .or_default()
+                    .push((tile_idx, orientation_idx));
+            }
+        }
+
+        // anything that fits against a side has it, read the other way, as a west side in
+        // some orientation
+        let shared_edges: Vec<usize> = (0..tiles.len())
+            .map(|tile_idx| {
+                tiles[tile_idx]
+                    .sides()
+                    .iter()
+                    .filter(|side| {
+                        by_west
+                            .get(&side.inverse())
+                            .into_iter()
+                            .flatten()
+                            .any(|(other, _)| *other != tile_idx)
+                    })
+                    .count()
+            })
+            .collect();
+        let mut starts: Vec<usize> = (0..tiles.len()).collect();
+        starts.sort_by_key(|tile_idx| (shared_edges[*tile_idx], tiles[*tile_idx].id));
+
+        Search {
+            tiles,
+            sides,
+            by_west,
+            by_north,
+            shared_edges,
+            starts,
+        }
+    }
+
+    fn tiles_sharing_at_least(&self, edges: usize) -> usize {
+        self.shared_edges
+            .iter()
+            .filter(|shared| **shared >= edges)
+            .count()
+    }
+
+    // in a `width` by `height` grid the inner tiles have four neighbours, the rest of the
+    // outside ones three and the corners two, so there have to be enough tiles with that
+    // many shared edges before a search is worth starting
+    fn could_fit(&self, width: usize, height: usize) -> bool {
+        let inner = (width - 2) * (height - 2);
+        let outside = 2 * (width - 2) + 2 * (height - 2);
+        self.tiles_sharing_at_least(4) >= inner
+            && self.tiles_sharing_at_least(3) >= inner + outside
+            && self.tiles_sharing_at_least(2) >= width * height
+    }
+
+    // the only layout that fits the counts exactly, if there are 4 corners and no tile
+    // shares fewer than two edges
+    fn derived_layout(&self) -> Option<(usize, usize)> {
+        let exactly = |edges: usize| {
+            self.shared_edges
+                .iter()
+                .filter(|shared| **shared == edges)
+                .count()
+        };
+        if exactly(2) != 4 || exactly(2) + exactly(3) + exactly(4) != self.tiles.len() {
+            return None;
+        }
+        // the outside tiles go round both pairs of sides once
+        let outside = exactly(3);
+        if outside % 2 != 0 {
+            return None;
+        }
+        let half_perimeter = outside / 2 + 4;
+        (2..=half_perimeter / 2)
+            .map(|height| (half_perimeter - height, height))
+            .find(|(width, height)| (width - 2) * (height - 2) == exactly(4))
+    }
+
+    fn run(&self, width: usize, height: usize) -> Option<Layout> {
+        let mut placed = Vec::with_capacity(width * height);
+        let mut used = vec![false; self.tiles.len()];
+        if !self.place(width, height, &mut placed, &mut used) {
+            return None;
+        }
+        let tiles = placed
+            .chunks(width)
+            .map(|row| {
+                row.iter()
+                    .map(|(tile_idx, orientation_idx)| {
+                        self.tiles[*tile_idx].apply(Orientation::ALL[*orientation_idx])
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(Layout {
+            width,
+            height,
+            tiles,
+        })
+    }
+
+    // fill in the grid a row at a time, left to right
+    fn place(
+        &self,
+        width: usize,
+        height: usize,
+        placed: &mut Vec<(usize, usize)>,
+        used: &mut [bool],
+    ) -> bool {
+        let position = placed.len();
+        if position == width * height {
+            return true;
+        }
+        let (row, col) = (position / width, position % width);
+        let side_of = |position: usize, side: usize| {
+            let (tile_idx, orientation_idx) = placed[position];
+            &self.sides[tile_idx][orientation_idx][side]
+        };
+
+        // our west side has to be our western neighbour's east side read the other way,
+        // and the same goes for north and south
+        let candidates: Vec<(usize, usize)> = if col > 0 {
+            let wanted = side_of(position - 1, EAST).inverse();
+            self.by_west.get(&wanted).cloned().unwrap_or_default()
+        } else if row > 0 {
+            let wanted = side_of(position - width, SOUTH).inverse();
+            self.by_north.get(&wanted).cloned().unwrap_or_default()
+        } else {
+            self.starts
+                .iter()
+                .flat_map(|tile_idx| (0..Orientation::ALL.len()).map(move |o| (*tile_idx, o)))
+                .collect()
+        };
+        let north = if row > 0 && col > 0 {
+            Some(side_of(position - width, SOUTH).inverse())
+        } else {
+            None
+        };
+
+        for (tile_idx, orientation_idx) in candidates {
+            if used[tile_idx] {
+                continue;
+            }
+            if let Some(north) = &north {
+                if self.sides[tile_idx][orientation_idx][NORTH] != *north {
+                    continue;
+                }
+            }
+            used[tile_idx] = true;
+            placed.push((tile_idx, orientation_idx));
+            if self.place(width, height, placed, used) {
+                return true;
+            }
+            placed.pop();
+            used[tile_idx] = false;
+        }
+        false
+    }
+}
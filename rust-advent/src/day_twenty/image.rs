@@ -0,0 +1,189 @@
+use super::assembly::Layout;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Dump the image in blocks, one per tile, but only if someone is going to see it
+pub fn trace_image(image: &[Vec<bool>], block: usize) {
+    if !crate::log::enabled(crate::log::Level::Trace) {
+        return;
+    }
+    for (r, row) in image.iter().enumerate() {
+        if r % block == 0 {
+            trace!("");
+        }
+        let mut row_str = "".to_string();
+        for (c, col) in row.iter().enumerate() {
+            if c % block == 0 {
+                row_str.push(' ');
+            }
+            row_str.push(if *col { '#' } else { '.' });
+        }
+        trace!("{}", row_str);
+    }
+}
+
+/// File formats the stitched image can be written as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Plain (P1) portable bitmap, '#' in black
+    Pbm,
+    /// Plain (P2) portable graymap, the same but with border cells in grey
+    Pgm,
+    /// '#' and '.', with a gap between tiles
+    Ascii,
+}
+
+impl ImageFormat {
+    /// Guess the format from a file name, e.g. `sea.pgm`
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ImageFormat, String> {
+        match s {
+            "pbm" => Ok(ImageFormat::Pbm),
+            "pgm" => Ok(ImageFormat::Pgm),
+            "ascii" | "txt" => Ok(ImageFormat::Ascii),
+            _ => Err(format!(
+                "unknown image format '{}', expected pbm, pgm or ascii",
+                s
+            )),
+        }
+    }
+}
+
+/// How to write out an assembled layout
+#[derive(Debug, Clone, Copy)]
+pub struct Export {
+    pub format: ImageFormat,
+    /// Keep the tile borders instead of stripping them like part two does
+    pub keep_borders: bool,
+    /// Write each tile's id across its middle row, for ascii only
+    pub tile_ids: bool,
+}
+
+/// The layout as an image file, with `border` cells stripped from each tile edge unless
+/// `keep_borders` is set
+pub fn export(layout: &Layout, border: usize, export: &Export) -> String {
+    let tile_size = layout.tiles[0][0].size();
+    // from here on `border` is how much of each block (one per tile) is border
+    let (image, block, border) = if export.keep_borders {
+        (layout.stitch(0), tile_size, border)
+    } else {
+        (layout.stitch(border), tile_size - 2 * border, 0)
+    };
+    let comment = format!(
+        "{}x{} tiles of {}x{}",
+        layout.width, layout.height, block, block
+    );
+    match export.format {
+        ImageFormat::Pbm => to_pbm(&image, &comment),
+        ImageFormat::Pgm => {
+            let in_border = |idx: usize| idx % block < border || idx % block >= block - border;
+            to_pgm(&image, &comment, |row_idx, col_idx| {
+                in_border(row_idx) || in_border(col_idx)
+            })
+        }
+        ImageFormat::Ascii => {
+            let mut lines = to_ascii(&image, block);
+            if export.tile_ids {
+                overlay_ids(&mut lines, layout, block);
+            }
+            lines.iter().map(|line| format!("{}\n", line)).collect()
+        }
+    }
+}
+
+// plain pnm files shouldn't have lines longer than 70 characters
+fn wrap(values: impl Iterator<Item = String>, separator: &str) -> String {
+    let mut out = String::new();
+    let mut line_len = 0;
+    for value in values {
+        if line_len > 0 && line_len + separator.len() + value.len() > 70 {
+            out.push('\n');
+            line_len = 0;
+        } else if line_len > 0 {
+            out.push_str(separator);
+            line_len += separator.len();
+        }
+        line_len += value.len();
+        out.push_str(&value);
+    }
+    out.push('\n');
+    out
+}
+
+/// A plain PBM file, one bit per cell with '#' as 1 (black)
+pub fn to_pbm(image: &[Vec<bool>], comment: &str) -> String {
+    let width = image.first().map_or(0, |row| row.len());
+    let mut out = format!("P1\n# {}\n{} {}\n", comment, width, image.len());
+    for row in image {
+        let bits = row
+            .iter()
+            .map(|cell| String::from(if *cell { "1" } else { "0" }));
+        out.push_str(&wrap(bits, ""));
+    }
+    out
+}
+
+/// A plain PGM file with '#' black and '.' white, or darker and lighter grey where
+/// `is_border` says so
+pub fn to_pgm(
+    image: &[Vec<bool>],
+    comment: &str,
+    is_border: impl Fn(usize, usize) -> bool,
+) -> String {
+    let width = image.first().map_or(0, |row| row.len());
+    let mut out = format!("P2\n# {}\n{} {}\n255\n", comment, width, image.len());
+    for (row_idx, row) in image.iter().enumerate() {
+        let shades = row.iter().enumerate().map(|(col_idx, cell)| {
+            let shade = match (*cell, is_border(row_idx, col_idx)) {
+                (true, false) => 0,
+                (true, true) => 64,
+                (false, true) => 192,
+                (false, false) => 255,
+            };
+            shade.to_string()
+        });
+        out.push_str(&wrap(shades, " "));
+    }
+    out
+}
+
+/// '#' and '.' in blocks of `block` cells, one per tile, split up by spaces and blank lines
+pub fn to_ascii(image: &[Vec<bool>], block: usize) -> Vec<String> {
+    let mut lines = vec![];
+    for (row_idx, row) in image.iter().enumerate() {
+        if row_idx > 0 && row_idx % block == 0 {
+            lines.push(String::new());
+        }
+        let mut line = String::new();
+        for (col_idx, cell) in row.iter().enumerate() {
+            if col_idx > 0 && col_idx % block == 0 {
+                line.push(' ');
+            }
+            line.push(if *cell { '#' } else { '.' });
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// write each id over the middle row of its tile, cut short if the tile is too narrow
+fn overlay_ids(lines: &mut [String], layout: &Layout, block: usize) {
+    for (tile_row_idx, tile_row) in layout.tiles.iter().enumerate() {
+        // each row of tiles is followed by a blank line
+        let line_idx = tile_row_idx * (block + 1) + block / 2;
+        let mut line: Vec<char> = lines[line_idx].chars().collect();
+        for (tile_col_idx, tile) in tile_row.iter().enumerate() {
+            let id: Vec<char> = tile.id.to_string().chars().take(block).collect();
+            let start = tile_col_idx * (block + 1) + (block - id.len()) / 2;
+            line[start..start + id.len()].copy_from_slice(&id);
+        }
+        lines[line_idx] = line.into_iter().collect();
+    }
+}
@@ -0,0 +1,137 @@
+use super::tiles::Orientation;
+use crate::ParseError;
+use std::collections::HashSet;
+
+const DAY: usize = 20;
+
+/// The one part two is after
+pub const SEA_MONSTER: &str = "                  # \n#    ##    ##    ###\n #  #  #  #  #  #   ";
+
+/// Cells that all have to be '#' for a pattern to show up somewhere, as (row, col)
+/// offsets from the top left of the smallest box around them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub cells: Vec<(usize, usize)>,
+    pub height: usize,
+    pub width: usize,
+}
+
+/// Somewhere a pattern showed up in an image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Top left of the pattern's box in the image
+    pub row: usize,
+    pub col: usize,
+    /// Which way round the pattern was
+    pub orientation: Orientation,
+    /// The image cells it covers
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Pattern {
+    /// Read a pattern drawn with '#', anything else being a cell that doesn't matter
+    pub fn parse(text: &str) -> Result<Pattern, ParseError> {
+        let mut cells = vec![];
+        for (row_idx, line) in text.lines().enumerate() {
+            for (col_idx, c) in line.chars().enumerate() {
+                if c == '#' {
+                    cells.push((row_idx, col_idx));
+                }
+            }
+        }
+        if cells.is_empty() {
+            return Err(ParseError::input(DAY, "the pattern has no '#' in it"));
+        }
+        Ok(Pattern::from_cells(&cells))
+    }
+
+    pub fn sea_monster() -> Pattern {
+        Pattern::parse(SEA_MONSTER).unwrap()
+    }
+
+    // shift the cells up against the top and left, and sort them so the same shape always
+    // comes out the same
+    fn from_cells(cells: &[(usize, usize)]) -> Pattern {
+        let top = cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+        let left = cells.iter().map(|(_, col)| *col).min().unwrap_or(0);
+        let mut cells: Vec<(usize, usize)> = cells
+            .iter()
+            .map(|(row, col)| (row - top, col - left))
+            .collect();
+        cells.sort_unstable();
+        Pattern {
+            height: cells.iter().map(|(row, _)| row + 1).max().unwrap_or(0),
+            width: cells.iter().map(|(_, col)| col + 1).max().unwrap_or(0),
+            cells,
+        }
+    }
+
+    /// The pattern turned and/or mirrored
+    pub fn oriented(&self, orientation: Orientation) -> Pattern {
+        let mut grid = vec![vec![false; self.width]; self.height];
+        for (row, col) in &self.cells {
+            grid[*row][*col] = true;
+        }
+        let grid = orientation.apply_to_grid(&grid);
+        let cells: Vec<(usize, usize)> = grid
+            .iter()
+            .enumerate()
+            .flat_map(|(row_idx, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, cell)| **cell)
+                    .map(move |(col_idx, _)| (row_idx, col_idx))
+            })
+            .collect();
+        Pattern::from_cells(&cells)
+    }
+
+    /// Every place the pattern shows up, in any of the eight orientations.  Orientations
+    /// that give the same shape (for a symmetric pattern) are only searched once.
+    pub fn find(&self, image: &[Vec<bool>]) -> Vec<Match> {
+        let height = image.len();
+        let width = image.first().map_or(0, |row| row.len());
+        let mut shapes: Vec<Pattern> = vec![];
+        let mut matches = vec![];
+        for orientation in &Orientation::ALL {
+            let shape = self.oriented(*orientation);
+            if shapes.contains(&shape) {
+                continue;
+            }
+            // images smaller than the pattern just don't have any
+            for row in 0..(height + 1).saturating_sub(shape.height) {
+                for col in 0..(width + 1).saturating_sub(shape.width) {
+                    if shape.cells.iter().all(|(r, c)| image[row + r][col + c]) {
+                        debug!("found one at {},{} {:?}", row, col, orientation);
+                        matches.push(Match {
+                            row,
+                            col,
+                            orientation: *orientation,
+                            cells: shape
+                                .cells
+                                .iter()
+                                .map(|(r, c)| (row + r, col + c))
+                                .collect(),
+                        });
+                    }
+                }
+            }
+            shapes.push(shape);
+        }
+        matches
+    }
+}
+
+/// How many '#' in the image aren't part of any match.  Matches can overlap, so each
+/// cell is only taken away once.
+pub fn roughness(image: &[Vec<bool>], matches: &[Match]) -> usize {
+    let matched: HashSet<(usize, usize)> = matches
+        .iter()
+        .flat_map(|found| found.cells.iter().copied())
+        .collect();
+    let total_octothorpe: usize = image
+        .iter()
+        .map(|row| row.iter().filter(|&val| *val).count())
+        .sum();
+    total_octothorpe - matched.len()
+}
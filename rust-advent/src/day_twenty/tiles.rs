@@ -1,31 +1,114 @@
 use std::fmt;
 
-#[derive(Debug, Clone)]
-pub enum Rotation {
-    Deg0,
-    Deg90,
-    Deg180,
-    Deg270,
+/// One of the eight ways a square can be laid down (the dihedral group of the square):
+/// mirrored left to right or not, then turned clockwise a number of quarter turns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    quarter_turns: u8,
+    mirrored: bool,
 }
 
-#[derive(Debug, Clone)]
-pub enum Flip {
-    NoFlip,
-    Horiz,
-    Vert,
-}
+impl Orientation {
+    pub const IDENTITY: Orientation = Orientation {
+        quarter_turns: 0,
+        mirrored: false,
+    };
+
+    pub const ALL: [Orientation; 8] = [
+        Orientation::new(0, false),
+        Orientation::new(1, false),
+        Orientation::new(2, false),
+        Orientation::new(3, false),
+        Orientation::new(0, true),
+        Orientation::new(1, true),
+        Orientation::new(2, true),
+        Orientation::new(3, true),
+    ];
+
+    pub const fn new(quarter_turns: u8, mirrored: bool) -> Orientation {
+        Orientation {
+            quarter_turns: quarter_turns % 4,
+            mirrored,
+        }
+    }
+
+    pub fn quarter_turns(&self) -> u8 {
+        self.quarter_turns
+    }
+
+    pub fn is_mirrored(&self) -> bool {
+        self.mirrored
+    }
+
+    /// `self` followed by `then`, as one orientation
+    pub fn compose(&self, then: Orientation) -> Orientation {
+        // mirroring after turning is the same as turning the other way after mirroring
+        let turns = if then.mirrored {
+            then.quarter_turns + 4 - self.quarter_turns
+        } else {
+            then.quarter_turns + self.quarter_turns
+        };
+        Orientation::new(turns, self.mirrored != then.mirrored)
+    }
+
+    /// The orientation that puts things back the way they were
+    pub fn inverse(&self) -> Orientation {
+        if self.mirrored {
+            // mirrored ones undo themselves
+            *self
+        } else {
+            Orientation::new(4 - self.quarter_turns, false)
+        }
+    }
+
+    /// Lay down a grid this way round.  Turning a grid that isn't square swaps its width
+    /// and height.
+    pub fn apply_to_grid<T: Clone>(&self, grid: &[Vec<T>]) -> Vec<Vec<T>> {
+        let mut result: Vec<Vec<T>> = if self.mirrored {
+            grid.iter()
+                .map(|row| row.iter().rev().cloned().collect())
+                .collect()
+        } else {
+            grid.to_vec()
+        };
+        for _ in 0..self.quarter_turns {
+            let height = result.len();
+            let width = result.first().map_or(0, |row| row.len());
+            result = (0..width)
+                .map(|row_idx| {
+                    (0..height)
+                        .map(|col_idx| result[height - 1 - col_idx][row_idx].clone())
+                        .collect()
+                })
+                .collect();
+        }
+        result
+    }
 
-#[derive(Debug, Clone)]
-pub struct Transform {
-    rotation: Rotation,
-    flip: Flip,
+    /// Where the sides of a tile end up, given as north, east, south and west each read
+    /// clockwise (as `Tile` keeps them)
+    pub fn apply_to_sides(&self, sides: &[SideVals; 4]) -> [SideVals; 4] {
+        let mut result = sides.clone();
+        if self.mirrored {
+            // east and west swap over, and reading clockwise now runs the other way
+            result = [
+                sides[0].inverse(),
+                sides[3].inverse(),
+                sides[2].inverse(),
+                sides[1].inverse(),
+            ];
+        }
+        // turning clockwise moves every side round one, and they still read clockwise
+        result.rotate_right(self.quarter_turns as usize);
+        result
+    }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SideVals {
     // Flipped just reverses these values
-    normal: u32,  // 1010011
-    inverse: u32, // 1100101
+    normal: Vec<bool>,  // 1010011
+    inverse: Vec<bool>, // 1100101
 }
 
 impl SideVals {
@@ -39,151 +122,111 @@ impl SideVals {
         }
     }
 
-    fn inverse(&self) -> SideVals {
+    /// The same side read the other way, which is what a neighbour's side has to be to
+    /// fit against this one
+    pub fn inverse(&self) -> SideVals {
         SideVals {
-            normal: self.inverse,
-            inverse: self.normal,
+            normal: self.inverse.clone(),
+            inverse: self.normal.clone(),
         }
     }
 }
 
 impl fmt::Display for SideVals {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {})", self.normal, self.inverse)
+        let bits =
+            |side: &[bool]| -> String { side.iter().map(|b| if *b { '1' } else { '0' }).collect() };
+        write!(f, "({}, {})", bits(&self.normal), bits(&self.inverse))
     }
 }
 
 fn to_numbers(bin: &Vec<bool>) -> SideVals {
-    // convert the boolean array into an int
-    let arr = bin.iter().map(|b| if *b { '1' } else { '0' });
-
-    let first_str: String = arr.collect();
-    let first = u32::from_str_radix(&first_str, 2).unwrap();
-    let second_str: String = first_str.chars().rev().collect();
-    let second = u32::from_str_radix(&second_str, 2).unwrap();
+    // sides are compared as bits rather than packed into an int, so tiles can be any size
     SideVals {
-        normal: first,
-        inverse: second,
+        normal: bin.to_vec(),
+        inverse: bin.iter().rev().copied().collect(),
     }
 }
 
 #[derive(Clone)]
 pub struct Tile {
     pub id: usize,
-    pub data: [[bool; 10]; 10], // Raw data at no rotation, no flip
+    pub data: Vec<Vec<bool>>, // Raw data at no rotation, no flip, always square
     all: [SideVals; 4],
-    pub linked_ids: [usize; 4],
 }
 
 impl Tile {
-    pub fn new(id: usize, data: [[bool; 10]; 10]) -> Tile {
+    pub fn new(id: usize, data: Vec<Vec<bool>>) -> Tile {
         // calculate the numerical representations of each side, calculated clockwise
         /*
         001    North: 1, East: 4, South: 1, West: 6
         100
         100
          */
-        let north = to_numbers(&data[0].to_vec());
-        let south = to_numbers(&data[9].to_vec()).inverse();
-        let east = to_numbers(&data.iter().map(|row| row[9]).collect());
-        let west = to_numbers(&data.iter().map(|row| row[0]).collect()).inverse();
+        let last = data.len() - 1;
+        let north = to_numbers(&data[0]);
+        let south = to_numbers(&data[last]).inverse();
+        let east = to_numbers(&data.iter().map(|row| row[last]).collect::<Vec<bool>>());
+        let west = to_numbers(&data.iter().map(|row| row[0]).collect::<Vec<bool>>()).inverse();
 
         // to make sure the sidevals don't change when you rotate, South and West need to be inverted
         Tile {
-            id: id,
-            data: data,
+            id,
+            data,
             all: [north, east, south, west],
-            linked_ids: [0; 4],
         }
     }
 
-    pub fn match_any_side(&self, other: &Tile) -> Option<(usize, Transform)> {
+    /// Which of our sides `other` fits against, and how to lay `other` down so that its
+    /// opposite side lines up with it
+    pub fn match_any_side(&self, other: &Tile) -> Option<(usize, Orientation)> {
         for (idx, side) in self.all.iter().enumerate() {
-            for (other_idx, other_side) in other.all.iter().enumerate() {
-                match side.matches(other_side) {
-                    None => (),
-                    Some(do_flip) => {
-                        // rotation needs to put the other tile so its opposite face
-                        // matches our tile's face
-                        // self: 0 means other: 2
-                        let target_side = (idx + 2) % 4;
-                        let diff = if target_side > other_idx {
-                            target_side - other_idx
-                        } else {
-                            target_side + 4 - other_idx
-                        };
-                        let rotation = match diff {
-                            0 => Rotation::Deg0,
-                            1 => Rotation::Deg90,
-                            2 => Rotation::Deg180,
-                            3 => Rotation::Deg270,
-                            4 => Rotation::Deg0,
-                            _ => panic!("WRONG INDEX DIFF!!!"),
-                        };
-                        return Some((
-                            idx,
-                            Transform {
-                                rotation: rotation,
-                                flip: if do_flip {
-                                    if idx == 0 || idx == 2 {
-                                        Flip::Horiz
-                                    } else {
-                                        Flip::Vert
-                                    }
-                                } else {
-                                    Flip::NoFlip
-                                },
-                            },
-                        ));
-                    }
-                }
+            if !other
+                .all
+                .iter()
+                .any(|other_side| side.matches(other_side).is_some())
+            {
+                continue;
+            }
+            // self: 0 means other: 2
+            let target_side = (idx + 2) % 4;
+            // neighbouring sides read clockwise run in opposite directions
+            let orientation = Orientation::ALL.iter().find(|orientation| {
+                let sides = orientation.apply_to_sides(&other.all);
+                side.matches(&sides[target_side]) == Some(false)
+            });
+            if let Some(orientation) = orientation {
+                return Some((idx, *orientation));
             }
         }
         None
     }
     pub fn get_matched_tile(&self, other: &Tile) -> Option<(usize, Tile)> {
-        match self.match_any_side(other) {
-            None => None,
-            Some((my_side, transform)) => Some((my_side, other.apply(transform))),
-        }
+        self.match_any_side(other)
+            .map(|(my_side, orientation)| (my_side, other.apply(orientation)))
     }
 
-    fn apply(&self, transform: Transform) -> Tile {
-        let mut rot_data = [[false; 10]; 10];
-
-        // Apply rotation first
-        for (row_idx, row) in self.data.iter().enumerate() {
-            for (col_idx, val) in row.iter().enumerate() {
-                match &transform.rotation {
-                    Rotation::Deg0 => rot_data[row_idx][col_idx] = *val,
-                    Rotation::Deg90 => rot_data[col_idx][9 - row_idx] = *val,
-                    Rotation::Deg180 => rot_data[9 - row_idx][9 - col_idx] = *val,
-                    Rotation::Deg270 => rot_data[9 - col_idx][row_idx] = *val,
-                }
-            }
-        }
+    /// North, east, south and west, each read clockwise
+    pub fn sides(&self) -> &[SideVals; 4] {
+        &self.all
+    }
 
-        // Then apply flip in the appropriate direction
-        let mut new_data = [[false; 10]; 10];
-        for (row_idx, row) in rot_data.iter().enumerate() {
-            for (col_idx, val) in row.iter().enumerate() {
-                match &transform.flip {
-                    Flip::NoFlip => new_data[row_idx][col_idx] = *val,
-                    Flip::Vert => new_data[9 - row_idx][col_idx] = *val,
-                    Flip::Horiz => new_data[row_idx][9 - col_idx] = *val,
-                }
-            }
-        }
+    /// Number of cells along each side
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
 
-        Tile::new(self.id, new_data)
+    /// A copy of this tile laid down the given way round
+    pub fn apply(&self, orientation: Orientation) -> Tile {
+        Tile::new(self.id, orientation.apply_to_grid(&self.data))
     }
 }
 
 impl fmt::Display for Tile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut output = format!(
-            "ID: {} N: {} E: {} S: {} W: {}\n{:?}\n",
-            self.id, self.all[0], self.all[1], self.all[2], self.all[3], self.linked_ids
+            "ID: {} N: {} E: {} S: {} W: {}\n",
+            self.id, self.all[0], self.all[1], self.all[2], self.all[3]
         );
         for row in &self.data {
             let mut row_out = row.iter().fold(String::from(""), |mut acc, c| {
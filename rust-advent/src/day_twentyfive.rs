@@ -0,0 +1,183 @@
+use super::{Answer, ParseError, SolveError, Solver};
+use std::collections::HashMap;
+
+const DAY: usize = 25;
+
+const DEFAULT_SUBJECT: u64 = 7;
+const DEFAULT_MODULUS: u64 = 20201227;
+
+/// Most baby steps `discrete_log` will keep in memory (about 100MB worth), which limits
+/// it to moduli up to `MAX_BABY_STEPS`²
+pub const MAX_BABY_STEPS: u64 = 1 << 22;
+
+/// base^exp mod modulus, by repeated squaring.  There is nothing mod 0, so that gives 0.
+pub fn modpow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    if modulus <= 1 {
+        return 0;
+    }
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1u128;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Find the smallest x with base^x = target (mod modulus) using baby-step giant-step,
+/// in O(sqrt(modulus)) time and memory.  `base` has to be coprime with `modulus`.
+/// Gives `None` for a modulus of 0, or one that would need more than `MAX_BABY_STEPS`.
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let target = target % modulus;
+    if modulus == 1 || target == 1 {
+        return Some(0);
+    }
+    let mul = |a: u64, b: u64| (a as u128 * b as u128 % modulus as u128) as u64;
+    let n = (modulus as f64).sqrt().ceil() as u64;
+    if n > MAX_BABY_STEPS {
+        return None;
+    }
+
+    // baby steps: target * base^j for j in 0..n, keeping the largest j so x comes out smallest
+    let mut baby_steps = HashMap::with_capacity(n as usize);
+    let mut value = target;
+    for j in 0..n {
+        baby_steps.insert(value, j);
+        value = mul(value, base);
+    }
+
+    // giant steps: base^(i * n) = target * base^j  =>  x = i * n - j
+    let giant_step = modpow(base, n, modulus);
+    let mut value = 1;
+    for i in 1..=n {
+        value = mul(value, giant_step);
+        if let Some(j) = baby_steps.get(&value) {
+            return Some(i * n - j);
+        }
+    }
+    None
+}
+
+pub struct DayTwentyFiveSolver {
+    card_key: u64,
+    door_key: u64,
+    subject: u64,
+    modulus: u64,
+}
+
+impl Solver for DayTwentyFiveSolver {
+    fn from_input(input: &str) -> Result<Box<DayTwentyFiveSolver>, ParseError> {
+        let mut keys = vec![];
+        let mut subject = DEFAULT_SUBJECT;
+        let mut modulus = DEFAULT_MODULUS;
+
+        // two public keys, optionally followed by 'subject: N' and 'modulus: M'
+        for (line_idx, line) in input.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let (name, value, col_idx) = match trimmed.find(':') {
+                Some(idx) => (&trimmed[..idx], trimmed[idx + 1..].trim(), idx + 1),
+                None => ("key", trimmed, 0),
+            };
+            let value: u64 = match value.parse() {
+                Ok(value) => value,
+                Err(error) => {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        col_idx,
+                        line,
+                        format!("expected a number: {}", error),
+                    ))
+                }
+            };
+            match name.trim() {
+                "key" if keys.len() < 2 => keys.push(value),
+                "key" => {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        0,
+                        line,
+                        "expected only two public keys",
+                    ))
+                }
+                "subject" => subject = value,
+                "modulus" => modulus = value,
+                _ => {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        0,
+                        line,
+                        "expected a public key, 'subject: N' or 'modulus: M'",
+                    ))
+                }
+            }
+        }
+
+        if keys.len() != 2 {
+            return Err(ParseError::input(
+                DAY,
+                format!("expected two public keys, found {}", keys.len()),
+            ));
+        }
+        if modulus < 2 {
+            return Err(ParseError::input(DAY, "modulus must be at least 2"));
+        }
+        if gcd(subject, modulus) != 1 {
+            return Err(ParseError::input(
+                DAY,
+                format!(
+                    "subject {} must be coprime with modulus {}",
+                    subject, modulus
+                ),
+            ));
+        }
+
+        Ok(Box::new(DayTwentyFiveSolver {
+            card_key: keys[0],
+            door_key: keys[1],
+            subject,
+            modulus,
+        }))
+    }
+
+    fn part_one(&self) -> Result<Answer, SolveError> {
+        // rather than running out of memory building the table
+        if (self.modulus as f64).sqrt().ceil() as u64 > MAX_BABY_STEPS {
+            return Err(SolveError::NoSolution(format!(
+                "modulus {} is too big for baby-step giant-step, which handles up to {}",
+                self.modulus,
+                MAX_BABY_STEPS * MAX_BABY_STEPS
+            )));
+        }
+        let card_loop =
+            discrete_log(self.subject, self.card_key, self.modulus).ok_or_else(|| {
+                SolveError::NoSolution(format!(
+                    "card key {} is not a power of {} mod {}",
+                    self.card_key, self.subject, self.modulus
+                ))
+            })?;
+        debug!("card loop size: {}", card_loop);
+        let encryption_key = modpow(self.door_key, card_loop, self.modulus);
+        Ok(Answer::Int(encryption_key as usize))
+    }
+}
@@ -1,8 +1,10 @@
-use super::Solver;
+use super::{Answer, ParseError, SolveError, Solver};
 use std::collections::{HashMap, HashSet};
 use std::iter::Sum;
 use std::ops::Add;
 
+const DAY: usize = 24;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct HexCoord {
     x: isize,
@@ -96,51 +98,72 @@ impl DayTwentyFourSolver {
 }
 
 impl Solver for DayTwentyFourSolver {
-    fn from_input(input: &String) -> Result<Box<DayTwentyFourSolver>, String> {
+    fn from_input(input: &str) -> Result<Box<DayTwentyFourSolver>, ParseError> {
         let mut solver = DayTwentyFourSolver::new();
 
-        for line in input.lines() {
+        for (line_idx, line) in input.lines().enumerate() {
             let mut cur_vec: Vec<HexCoord> = vec![];
             let mut prev_token = 'a';
-            for token in line.chars() {
+            for (col_idx, token) in line.char_indices() {
+                let bad_token = || {
+                    ParseError::new(
+                        DAY,
+                        line_idx,
+                        col_idx,
+                        line,
+                        if prev_token == 'n' || prev_token == 's' {
+                            "expected 'e' or 'w' after 'n' or 's'"
+                        } else {
+                            "expected one of 'e', 'w', 'n' or 's'"
+                        },
+                    )
+                };
                 if prev_token == 'n' {
-                    if token == 'e' {
-                        cur_vec.push(HexCoord { x: 1, y: 1 })
-                    } else {
-                        // no input validation
-                        cur_vec.push(HexCoord { x: 1, y: 0 })
+                    match token {
+                        'e' => cur_vec.push(HexCoord { x: 1, y: 1 }),
+                        'w' => cur_vec.push(HexCoord { x: 1, y: 0 }),
+                        _ => return Err(bad_token()),
                     }
                 } else if prev_token == 's' {
-                    if token == 'e' {
-                        cur_vec.push(HexCoord { x: -1, y: 0 })
-                    } else {
-                        // no input validation
-                        cur_vec.push(HexCoord { x: -1, y: -1 })
+                    match token {
+                        'e' => cur_vec.push(HexCoord { x: -1, y: 0 }),
+                        'w' => cur_vec.push(HexCoord { x: -1, y: -1 }),
+                        _ => return Err(bad_token()),
                     }
                 } else {
                     // previous token was e or w
-                    if token == 'e' {
-                        cur_vec.push(HexCoord { x: 0, y: 1 })
-                    } else if token == 'w' {
-                        cur_vec.push(HexCoord { x: 0, y: -1 })
+                    match token {
+                        'e' => cur_vec.push(HexCoord { x: 0, y: 1 }),
+                        'w' => cur_vec.push(HexCoord { x: 0, y: -1 }),
+                        'n' | 's' => (),
+                        _ => return Err(bad_token()),
                     }
                 }
                 prev_token = token;
             }
+            if prev_token == 'n' || prev_token == 's' {
+                return Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    line.len(),
+                    line,
+                    "direction is missing its 'e' or 'w'",
+                ));
+            }
             solver.lines.push(cur_vec);
         }
 
         Ok(Box::new(solver))
     }
-    fn part_one(&self) -> Result<usize, &str> {
+    fn part_one(&self) -> Result<Answer, SolveError> {
         let set = self.initial_tiles();
-        Ok(set.len())
+        Ok(Answer::Int(set.len()))
     }
 
-    fn part_two(&self) -> Result<usize, &str> {
+    fn part_two(&self) -> Result<Answer, SolveError> {
         let mut black_tiles = self.initial_tiles();
 
-        for day in 1..101 {
+        for _day in 1..101 {
             let mut neighbors = HashMap::new();
 
             for tile in &black_tiles {
@@ -174,8 +197,8 @@ impl Solver for DayTwentyFourSolver {
                 }
             }
             black_tiles = new_black_tiles;
-            // println!("Day {}: {}", day, black_tiles.len());
+            // println!("Day {}: {}", _day, black_tiles.len());
         }
-        Ok(black_tiles.len())
+        Ok(Answer::Int(black_tiles.len()))
     }
 }
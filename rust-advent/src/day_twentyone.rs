@@ -1,7 +1,9 @@
-use super::Solver;
+use super::{Answer, ParseError, SolveError, Solver};
 use regex::Regex;
 use std::collections::{HashMap, HashSet, VecDeque};
 
+const DAY: usize = 21;
+
 pub struct DayTwentyOneSolver {
     // possible ingredients and how many there are of each
     ingredients: HashMap<String, usize>,
@@ -21,14 +23,25 @@ impl DayTwentyOneSolver {
 }
 
 impl Solver for DayTwentyOneSolver {
-    fn from_input(input: &String) -> Result<Box<DayTwentyOneSolver>, String> {
+    fn from_input(input: &str) -> Result<Box<DayTwentyOneSolver>, ParseError> {
         let mut solver = DayTwentyOneSolver::new();
 
         lazy_static! {
             static ref LINE_RE: Regex = Regex::new(r"(.*) \(contains (.*)\)").unwrap();
         }
-        for line in input.lines() {
-            let captures = LINE_RE.captures(line).unwrap();
+        for (line_idx, line) in input.lines().enumerate() {
+            let captures = match LINE_RE.captures(line) {
+                Some(captures) => captures,
+                None => {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        0,
+                        line,
+                        "expected '<ingredients> (contains <allergens>)'",
+                    ))
+                }
+            };
             let ingredients = captures[1].to_string();
             let allergies = captures[2].to_string();
             let mut line_ingredient_set = HashSet::<String>::new();
@@ -109,30 +122,31 @@ impl Solver for DayTwentyOneSolver {
         Ok(Box::new(solver))
     }
 
-    fn part_one(&self) -> Result<usize, &str> {
-        Ok(self
+    fn part_one(&self) -> Result<Answer, SolveError> {
+        let safe_count = self
             .ingredient_allergy_map
             .iter()
             .filter_map(|(ingredient, allergies)| {
-                if allergies.len() != 0 {
+                if !allergies.is_empty() {
                     None
                 } else {
                     Some(self.ingredients.get(ingredient).unwrap())
                 }
             })
-            .sum())
+            .sum();
+        Ok(Answer::Int(safe_count))
     }
-    fn part_two(&self) -> Result<usize, &str> {
-        println!("-- allergy -> ingredients");
+    fn part_two(&self) -> Result<Answer, SolveError> {
+        debug!("-- allergy -> ingredients");
         for (allergy, ingredients) in &self.allergy_ingredient_map {
             if ingredients.len() != 0 {
-                println!("{} -> {:?}", allergy, ingredients);
+                debug!("{} -> {:?}", allergy, ingredients);
             }
         }
-        println!("-- ingredient -> allergies");
+        debug!("-- ingredient -> allergies");
         for (ingredient, allergies) in &self.ingredient_allergy_map {
             if allergies.len() != 0 {
-                println!("{} -> {:?}", ingredient, allergies);
+                debug!("{} -> {:?}", ingredient, allergies);
             }
         }
         // copy the ingredient to allergy map so we can update it
@@ -152,8 +166,8 @@ impl Solver for DayTwentyOneSolver {
         }
         let mut loops = 0;
         loop {
-            println!("LOOP {}: {:?}", loops, queue);
-            println!("{:?}", final_list);
+            trace!("LOOP {}: {:?}", loops, queue);
+            trace!("{:?}", final_list);
 
             let (ingredient, allergy) = match queue.pop_front() {
                 None => break,
@@ -165,7 +179,7 @@ impl Solver for DayTwentyOneSolver {
                 if sub_ingredient == &ingredient {
                     continue;
                 }
-                println!("{:?}", sub_ingredient);
+                trace!("{:?}", sub_ingredient);
                 final_map
                     .entry(sub_ingredient.to_string())
                     .and_modify(|set| {
@@ -173,7 +187,7 @@ impl Solver for DayTwentyOneSolver {
                     });
                 let remaining = final_map.get_mut(sub_ingredient).unwrap();
                 if remaining.len() == 1 {
-                    println!("\t\t{} {:?}", sub_ingredient, remaining);
+                    trace!("\t\t{} {:?}", sub_ingredient, remaining);
                     queue.push_back((
                         sub_ingredient.to_string(),
                         remaining.iter().next().unwrap().to_string(),
@@ -188,7 +202,6 @@ impl Solver for DayTwentyOneSolver {
             .map(|(ingredient, _)| ingredient.to_string())
             .collect::<Vec<_>>()
             .join(",");
-        println!("{:?}", solution_string);
-        Ok(2)
+        Ok(Answer::Text(solution_string))
     }
 }
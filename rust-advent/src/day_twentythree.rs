@@ -1,5 +1,6 @@
-use super::Solver;
-use std::collections::HashSet;
+use super::{Answer, ParseError, SolveError, Solver};
+
+const DAY: usize = 23;
 
 #[derive(Clone, Debug)]
 pub struct DayTwentyThreeSolver {
@@ -17,22 +18,15 @@ impl DayTwentyThreeSolver {
         }
     }
 
-    fn print_state(&self, iters: usize) {
-        // print the first "iters" number of cups in the game, or all of them if there are fewer than that
-        let mut seen = HashSet::new();
-        let mut cur_cup = self.current_cup;
-        let mut printme = vec![];
-        let mut count = 0;
-        loop {
-            if seen.contains(&cur_cup) || count >= iters {
-                break;
-            }
-            count += 1;
-            seen.insert(cur_cup);
-            printme.push(cur_cup);
+    fn labels_after(&self, cup: usize) -> String {
+        // collect the labels of every cup clockwise from the given cup, not including it
+        let mut labels = String::new();
+        let mut cur_cup = self.next_cup[cup];
+        while cur_cup != cup {
+            labels.push_str(&cur_cup.to_string());
             cur_cup = self.next_cup[cur_cup];
         }
-        println!("{:?}", printme);
+        labels
     }
 
     fn do_turn(&mut self) {
@@ -85,23 +79,52 @@ impl DayTwentyThreeSolver {
 }
 
 impl Solver for DayTwentyThreeSolver {
-    fn from_input(input: &String) -> Result<Box<DayTwentyThreeSolver>, String> {
+    fn from_input(input: &str) -> Result<Box<DayTwentyThreeSolver>, ParseError> {
         let mut solver = DayTwentyThreeSolver::new();
-        let nums: Vec<usize> = input
-            .trim()
-            .chars()
-            .map(|l| l.to_string().parse().expect("BAD INPUT"))
-            .collect();
-        println!("{:?}", nums);
-        println!("{}", solver.next_cup.len());
+        let (line_idx, line) = match input
+            .lines()
+            .enumerate()
+            .find(|(_, line)| !line.trim().is_empty())
+        {
+            Some(found) => found,
+            None => return Err(ParseError::input(DAY, "no cup labels found")),
+        };
+        let mut nums: Vec<usize> = vec![];
+        for (col_idx, label) in line.char_indices() {
+            if label.is_whitespace() {
+                continue;
+            }
+            // labels have to be the digits 1 to n, each used once, for the cups to wrap around
+            match label.to_digit(10) {
+                Some(num) if num > 0 && !nums.contains(&(num as usize)) => nums.push(num as usize),
+                _ => {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        col_idx,
+                        line,
+                        "expected a cup label from 1 to 9 that has not been used yet",
+                    ))
+                }
+            }
+        }
+        if nums.len() < 5 || nums.iter().any(|num| *num > nums.len()) {
+            return Err(ParseError::new(
+                DAY,
+                line_idx,
+                0,
+                line,
+                "expected at least 5 cups labelled 1 to n",
+            ));
+        }
+        debug!("{:?}", nums);
+        trace!("{}", solver.next_cup.len());
         let first_num = nums[0];
+        solver.highest = nums.len();
         let mut prev_num = &first_num;
         for num in &nums[1..] {
-            println!("{}", num);
+            trace!("{}", num);
             solver.next_cup[*prev_num] = *num;
-            if num > &solver.highest {
-                solver.highest = *num;
-            }
             prev_num = num;
         }
         solver.next_cup[*prev_num] = first_num;
@@ -109,20 +132,16 @@ impl Solver for DayTwentyThreeSolver {
 
         Ok(Box::new(solver))
     }
-    fn part_one(&self) -> Result<usize, &str> {
+    fn part_one(&self) -> Result<Answer, SolveError> {
         let mut game = self.clone();
         for _iter in 0..100 {
             // println!("-- move {} --", _iter);
             game.do_turn();
         }
-        game.current_cup = 1;
-        game.print_state(10);
-
-        // TODO: is it worth turning it into a string?
-        Ok(1)
+        Ok(Answer::Text(game.labels_after(1)))
     }
 
-    fn part_two(&self) -> Result<usize, &str> {
+    fn part_two(&self) -> Result<Answer, SolveError> {
         let mut game = self.clone();
 
         // add all the remaing cups until the size reaches 1000000
@@ -139,19 +158,18 @@ impl Solver for DayTwentyThreeSolver {
         game.next_cup[last] = game.highest + 1;
         game.next_cup[1000000] = game.current_cup;
         game.highest = 1000000;
-        // game.print_state(15);
 
         // println!("{}", game.next_cup.len());
         for iter in 0..10000000 {
             if iter % 1000000 == 0 {
-                println!("Iter: {}", iter);
+                info!("Iter: {}", iter);
             }
             game.do_turn();
         }
         let one = game.next_cup[1];
         let two = game.next_cup[one];
-        println!("one: {}", one);
-        println!("two: {}", two);
-        Ok(one * two)
+        debug!("one: {}", one);
+        debug!("two: {}", two);
+        Ok(Answer::Int(one * two))
     }
 }
@@ -1,6 +1,8 @@
-use super::Solver;
+use super::{Answer, ParseError, SolveError, Solver};
 use std::collections::{HashSet, VecDeque};
 
+const DAY: usize = 22;
+
 #[derive(Clone)]
 pub struct DayTwentyTwoSolver {
     p1_cards: VecDeque<usize>,
@@ -28,7 +30,7 @@ impl DayTwentyTwoSolver {
             // check for infinite loop
             let cur_state = self.state_to_string();
             if seen_states.contains(&cur_state) {
-                println!("INFINITE LOOP: Player 1 wins!");
+                debug!("INFINITE LOOP: Player 1 wins!");
                 break;
             }
             seen_states.insert(self.state_to_string());
@@ -36,14 +38,14 @@ impl DayTwentyTwoSolver {
             // Get current cards.  If either player has none left, the other wins
             let p1_card = match self.p1_cards.pop_front() {
                 None => {
-                    println!("Player 2 wins!");
+                    debug!("Player 2 wins!");
                     break;
                 }
                 Some(card) => card,
             };
             let p2_card = match self.p2_cards.pop_front() {
                 None => {
-                    println!("Player 1 wins!");
+                    debug!("Player 1 wins!");
                     break;
                 }
                 Some(card) => card,
@@ -101,7 +103,7 @@ impl DayTwentyTwoSolver {
             // check for infinite loop
             let cur_state = self.state_to_string();
             if seen_states.contains(&cur_state) {
-                println!("DEPTH: {} INFINITE LOOP: Player 1 wins!", depth);
+                trace!("DEPTH: {} INFINITE LOOP: Player 1 wins!", depth);
                 break;
             }
             seen_states.insert(self.state_to_string());
@@ -109,14 +111,14 @@ impl DayTwentyTwoSolver {
             // Get current cards.  If either player has none left, the other wins
             let p1_card = match self.p1_cards.pop_front() {
                 None => {
-                    println!("DEPTH: {} Player 2 wins!", depth);
+                    trace!("DEPTH: {} Player 2 wins!", depth);
                     break;
                 }
                 Some(card) => card,
             };
             let p2_card = match self.p2_cards.pop_front() {
                 None => {
-                    println!("DEPTH: {} Player 1 wins!", depth);
+                    trace!("DEPTH: {} Player 1 wins!", depth);
                     break;
                 }
                 Some(card) => card,
@@ -151,10 +153,10 @@ impl DayTwentyTwoSolver {
 }
 
 impl Solver for DayTwentyTwoSolver {
-    fn from_input(input: &String) -> Result<Box<DayTwentyTwoSolver>, String> {
+    fn from_input(input: &str) -> Result<Box<DayTwentyTwoSolver>, ParseError> {
         let mut solver = DayTwentyTwoSolver::new();
         let mut p2_input = false;
-        for line in input.lines() {
+        for (line_idx, line) in input.lines().enumerate() {
             if line == "Player 1:" || line.len() == 0 {
                 continue;
             }
@@ -162,7 +164,18 @@ impl Solver for DayTwentyTwoSolver {
                 p2_input = true;
                 continue;
             }
-            let cur_card: usize = line.parse().expect("BAD INPUT!!");
+            let cur_card: usize = match line.parse() {
+                Ok(card) => card,
+                Err(_) => {
+                    return Err(ParseError::new(
+                        DAY,
+                        line_idx,
+                        0,
+                        line,
+                        "expected a card number or 'Player N:'",
+                    ))
+                }
+            };
             if p2_input {
                 solver.p2_cards.push_back(cur_card);
             } else {
@@ -171,7 +184,7 @@ impl Solver for DayTwentyTwoSolver {
         }
         Ok(Box::new(solver))
     }
-    fn part_one(&self) -> Result<usize, &str> {
+    fn part_one(&self) -> Result<Answer, SolveError> {
         let mut game = self.clone();
         game.play_game();
         let winning_deck = if game.p1_cards.is_empty() {
@@ -179,17 +192,18 @@ impl Solver for DayTwentyTwoSolver {
         } else {
             game.p1_cards
         };
-        Ok(winning_deck
+        let score = winning_deck
             .iter()
             .enumerate()
             .map(|(index, card)| {
-                println!("{}: {}", index, card);
+                trace!("{}: {}", index, card);
                 (winning_deck.len() - index) * card
             })
-            .sum())
+            .sum();
+        Ok(Answer::Int(score))
     }
 
-    fn part_two(&self) -> Result<usize, &str> {
+    fn part_two(&self) -> Result<Answer, SolveError> {
         let mut game = self.clone();
         game.play_recursive_game(0);
         let winning_deck = if game.p1_cards.is_empty() {
@@ -197,13 +211,14 @@ impl Solver for DayTwentyTwoSolver {
         } else {
             game.p1_cards
         };
-        Ok(winning_deck
+        let score = winning_deck
             .iter()
             .enumerate()
             .map(|(index, card)| {
-                println!("{}: {}", index, card);
+                trace!("{}: {}", index, card);
                 (winning_deck.len() - index) * card
             })
-            .sum())
+            .sum();
+        Ok(Answer::Int(score))
     }
 }
@@ -1,6 +1,7 @@
-use super::Solver;
+use super::{Answer, ParseError, SolveError, Solver};
 use regex::Regex;
-use std::num::ParseIntError;
+
+const DAY: usize = 2;
 
 #[derive(Debug)]
 struct RuleLine {
@@ -11,16 +12,41 @@ struct RuleLine {
 }
 
 impl RuleLine {
-    fn from_line(line: &str) -> Result<Self, ParseIntError> {
+    fn from_line(line_idx: usize, line: &str) -> Result<Self, ParseError> {
         lazy_static! {
             static ref LINE_RE: Regex = Regex::new(r"(\d*)-(\d*) ([a-z]): ([a-z]*)").unwrap();
         }
 
-        let captures = LINE_RE.captures(line).unwrap();
+        let captures = match LINE_RE.captures(line) {
+            Some(captures) => captures,
+            None => {
+                return Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    0,
+                    line,
+                    "expected '<min>-<max> <letter>: <password>'",
+                ))
+            }
+        };
+        // both numbers are 1-based positions in part two, so 0 is never valid
+        let number = |group: usize| {
+            let m = captures.get(group).unwrap();
+            match m.as_str().parse() {
+                Ok(val) if val > 0 => Ok(val),
+                _ => Err(ParseError::new(
+                    DAY,
+                    line_idx,
+                    m.start(),
+                    line,
+                    "expected a number greater than 0",
+                )),
+            }
+        };
 
         Ok(RuleLine {
-            min: captures[1].parse()?,
-            max: captures[2].parse()?,
+            min: number(1)?,
+            max: number(2)?,
             letter: captures[3].chars().next().unwrap(),
             password: captures[4].to_string(),
         })
@@ -49,25 +75,22 @@ pub struct DayTwoSolver {
 }
 
 impl Solver for DayTwoSolver {
-    fn from_input(input: &String) -> Result<Box<DayTwoSolver>, String> {
+    fn from_input(input: &str) -> Result<Box<DayTwoSolver>, ParseError> {
         let mut rules: Vec<RuleLine> = Vec::new();
 
-        for line in input.lines() {
-            rules.push(match RuleLine::from_line(line) {
-                Ok(val) => val,
-                Err(error) => return Err(error.to_string()),
-            });
+        for (line_idx, line) in input.lines().enumerate() {
+            rules.push(RuleLine::from_line(line_idx, line)?);
         }
         Ok(Box::new(DayTwoSolver { all_rules: rules }))
     }
 
-    fn part_one(&self) -> Result<usize, &str> {
+    fn part_one(&self) -> Result<Answer, SolveError> {
         let valid_rules = self.all_rules.iter().filter(|&r| r.is_valid_one()).count();
 
-        Ok(valid_rules)
+        Ok(Answer::Int(valid_rules))
     }
-    fn part_two(&self) -> Result<usize, &str> {
+    fn part_two(&self) -> Result<Answer, SolveError> {
         let valid_rules = self.all_rules.iter().filter(|&r| r.is_valid_two()).count();
-        Ok(valid_rules)
+        Ok(Answer::Int(valid_rules))
     }
 }
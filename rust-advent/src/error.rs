@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::fmt;
+
+/// A problem with a puzzle input, pointing at where in the input it happened.
+/// `line` and `column` are 1-based; a `line` of 0 means the problem is with the
+/// input as a whole (missing sections, no data at all, ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: usize,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// `line_idx` and `col_idx` are zero based, as they come out of `enumerate()`
+    pub fn new(
+        day: usize,
+        line_idx: usize,
+        col_idx: usize,
+        text: &str,
+        message: impl Into<String>,
+    ) -> ParseError {
+        ParseError {
+            day,
+            line: line_idx + 1,
+            column: col_idx + 1,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// An error that isn't tied to a single line of the input
+    pub fn input(day: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            day,
+            line: 0,
+            column: 0,
+            text: String::new(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "day {}: {}", self.day, self.message);
+        }
+        // point at the offending column underneath the line itself
+        let gutter = self.line.to_string();
+        write!(
+            f,
+            "day {}, line {}, column {}: {}\n {} | {}\n {} | {}^",
+            self.day,
+            self.line,
+            self.column,
+            self.message,
+            gutter,
+            self.text,
+            " ".repeat(gutter.len()),
+            " ".repeat(self.column.saturating_sub(1))
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Why a part could not produce an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    NotImplemented,
+    Parse(ParseError),
+    NoSolution(String),
+    /// The part doesn't make sense for this input, like day 19 part two on a grammar
+    /// without the rules it rewrites
+    NotApplicable(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NotImplemented => write!(f, "Not implemented yet!"),
+            SolveError::Parse(err) => write!(f, "{}", err),
+            SolveError::NoSolution(reason) => write!(f, "No solution found: {}", reason),
+            SolveError::NotApplicable(reason) => write!(f, "Skipped: {}", reason),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(err: ParseError) -> SolveError {
+        SolveError::Parse(err)
+    }
+}
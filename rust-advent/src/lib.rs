@@ -2,16 +2,31 @@
 extern crate lazy_static;
 extern crate regex;
 
+#[macro_use]
+pub mod log;
+
+mod answer;
+mod error;
+
+pub use answer::Answer;
+pub use error::{ParseError, SolveError};
+
 pub trait Solver {
-    fn from_input(input: &String) -> Result<Box<Self>, String>
+    fn from_input(input: &str) -> Result<Box<Self>, ParseError>
     where
         Self: Sized;
 
-    fn part_one(&self) -> Result<usize, &str> {
-        Err("Not implemented yet!")
+    /// Change a setting once the input has been parsed, e.g. the rule overrides
+    /// for day 19.  Returns `Ok(false)` if this day has no option called `name`.
+    fn set_option(&mut self, _name: &str, _value: &str) -> Result<bool, ParseError> {
+        Ok(false)
     }
-    fn part_two(&self) -> Result<usize, &str> {
-        Err("Not implemented yet!")
+
+    fn part_one(&self) -> Result<Answer, SolveError> {
+        Err(SolveError::NotImplemented)
+    }
+    fn part_two(&self) -> Result<Answer, SolveError> {
+        Err(SolveError::NotImplemented)
     }
 }
 
@@ -26,3 +41,8 @@ pub mod day_twentyfour;
 pub mod day_twentyone;
 pub mod day_twentythree;
 pub mod day_twentytwo;
+
+pub mod bench;
+pub mod check;
+pub mod registry;
+pub mod runner;
@@ -0,0 +1,77 @@
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How much the solvers should say while they work.  Everything goes to stderr
+/// so that answers on stdout stay easy to read and parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn label(&self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+// 0 means nothing is logged at all
+static MAX_LEVEL: AtomicUsize = AtomicUsize::new(Level::Error as usize);
+
+/// Set the most verbose level that still gets printed, or `None` to silence everything
+pub fn set_max_level(level: Option<Level>) {
+    MAX_LEVEL.store(level.map_or(0, |l| l as usize), Ordering::Relaxed);
+}
+
+/// Check before building anything expensive (like a whole image) just to log it
+pub fn enabled(level: Level) -> bool {
+    level as usize <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Used by the macros, which only format their arguments if the level is enabled
+pub fn log(level: Level, args: fmt::Arguments) {
+    eprintln!("[{}] {}", level.label(), args);
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Error) {
+            $crate::log::log($crate::log::Level::Error, format_args!($($arg)*))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            $crate::log::log($crate::log::Level::Info, format_args!($($arg)*))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            $crate::log::log($crate::log::Level::Debug, format_args!($($arg)*))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Trace) {
+            $crate::log::log($crate::log::Level::Trace, format_args!($($arg)*))
+        }
+    };
+}
@@ -1,42 +1,776 @@
-use advent2020;
-use advent2020::Solver;
+use advent2020::bench::{self, PhaseTiming};
+use advent2020::check::{self, CheckOutcome};
+use advent2020::day_nineteen::earley::Parser;
+use advent2020::day_nineteen::grammar::{self, Grammar};
+use advent2020::day_nineteen::language;
+use advent2020::day_twenty::image::{self, Export, ImageFormat};
+use advent2020::day_twenty::DayTwentySolver;
+use advent2020::log::{self, Level};
+use advent2020::registry::{self, SolverEntry, SolverOption};
+use advent2020::runner::{self, PartResult, Status};
+use advent2020::{Answer, SolveError, Solver};
 use std::error::Error;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::str::FromStr;
+use std::time::Duration;
 use structopt::StructOpt;
 
+// inputs are checked in next to the crate, not inside it, so look in both places
+const INPUTS_ROOTS: &[&str] = &["inputs", "../inputs"];
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "table" => Ok(Format::Table),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown format '{}', expected table, csv or json",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Work with the day 19 grammar: its rules, plus any --override rules, and its messages.
+    /// Parsing starts from rule 0 unless another is given with -o start=N
+    Grammar(GrammarCommand),
+    /// Assemble the day 20 tiles and write out the picture.  The border and layout options
+    /// (-o border=N, -o layout=WxH) apply here too
+    Image {
+        /// pbm, pgm or ascii; by default taken from the output file's extension, or ascii
+        #[structopt(long = "format")]
+        format: Option<ImageFormat>,
+        /// Keep the tile borders, which show up grey in pgm files
+        #[structopt(long = "borders")]
+        borders: bool,
+        /// Write each tile's id over it, for ascii only
+        #[structopt(long = "ids")]
+        ids: bool,
+        /// File to write, instead of standard output
+        #[structopt(parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum GrammarCommand {
+    /// Look for unreachable rules and more than one possible start rule
+    Check,
+    /// Print how each message is derived from the rules
+    Parse {
+        /// Print Graphviz DOT instead of an indented tree
+        #[structopt(long = "dot")]
+        dot: bool,
+        /// Most derivations to show per message, for ambiguous grammars
+        #[structopt(short = "n", long = "limit", default_value = "1")]
+        limit: usize,
+        /// Messages to parse instead of the ones in the input
+        messages: Vec<String>,
+    },
+    /// Print the shortest and longest string each rule can match
+    Lengths,
+    /// Count the distinct strings a rule matches
+    Count {
+        /// Only count strings up to this many characters
+        #[structopt(long = "max-len")]
+        max_length: Option<usize>,
+        /// Rule to count, instead of the start rule
+        rule: Option<String>,
+    },
+    /// List the strings a rule matches, shortest first
+    Enumerate {
+        /// Only list strings up to this many characters
+        #[structopt(long = "max-len")]
+        max_length: Option<usize>,
+        /// Stop after this many strings
+        #[structopt(short = "n", long = "limit")]
+        limit: Option<usize>,
+        /// Rule to list, instead of the start rule
+        rule: Option<String>,
+    },
+}
+
 #[derive(Debug, StructOpt)]
 struct Opt {
+    #[structopt(subcommand)]
+    command: Option<Command>,
     #[structopt(short = "d", long = "day", default_value = "1")]
     day: String,
+    /// Input file, or '-' to read stdin [default: <inputs root>/<day>/input.txt]
     #[structopt(short = "i", long = "input", parse(from_os_str))]
-    input: std::path::PathBuf,
-}
-
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = Opt::from_args();
-
-    // load the file
-    let content = std::fs::read_to_string(&args.input)?;
-
-    let day: Box<dyn Solver> = match &args.day[..] {
-        "1" => advent2020::day_one::DayOneSolver::from_input(&content)?,
-        "2" => advent2020::day_two::DayTwoSolver::from_input(&content)?,
-        "3" => advent2020::day_three::DayThreeSolver::from_input(&content)?,
-        "19" => advent2020::day_nineteen::DayNineteenSolver::from_input(&content)?,
-        "20" => advent2020::day_twenty::DayTwentySolver::from_input(&content)?,
-        "21" => advent2020::day_twentyone::DayTwentyOneSolver::from_input(&content)?,
-        "22" => advent2020::day_twentytwo::DayTwentyTwoSolver::from_input(&content)?,
-        "23" => advent2020::day_twentythree::DayTwentyThreeSolver::from_input(&content)?,
-        "24" => advent2020::day_twentyfour::DayTwentyFourSolver::from_input(&content)?,
-        "25" => advent2020::day_twentyfive::DayTwentyFiveSolver::from_input(&content)?,
-        _ => {
+    input: Option<PathBuf>,
+    /// Use <inputs root>/<day>/test.txt instead of input.txt
+    #[structopt(short = "t", long = "test")]
+    test: bool,
+    /// Directory holding one folder of inputs per day [default: ./inputs or ../inputs]
+    #[structopt(long = "inputs", parse(from_os_str), env = "ADVENT_INPUTS")]
+    inputs: Option<PathBuf>,
+    /// Run every registered day against its default input (or test.txt with --test)
+    #[structopt(short = "a", long = "all")]
+    all: bool,
+    /// Time from_input, part_one and part_two over N runs each
+    #[structopt(short = "b", long = "bench", parse(try_from_str = parse_runs))]
+    bench: Option<usize>,
+    /// Compare answers against the .answers file next to each input
+    #[structopt(short = "c", long = "check")]
+    check: bool,
+    /// Output format for results: table, csv or json (one object per line)
+    #[structopt(short = "f", long = "format")]
+    format: Option<Format>,
+    /// Set a solver option, as name=value; can be repeated
+    #[structopt(short = "o", long = "option", number_of_values = 1)]
+    options: Vec<SolverOption>,
+    /// Replace a grammar rule before solving day 19, e.g. "8: 42 | 42 8"; can be repeated
+    #[structopt(long = "override", number_of_values = 1)]
+    overrides: Vec<String>,
+    /// Read day 19 rule overrides from a file, one rule per line
+    #[structopt(long = "overrides-file", parse(from_os_str))]
+    overrides_file: Option<PathBuf>,
+    /// Show solver progress on stderr; repeat for more detail (-vv debug, -vvv trace)
+    #[structopt(short = "v", long = "verbose", parse(from_occurrences))]
+    verbose: u8,
+    /// Only print answers, not even solver errors
+    #[structopt(short = "q", long = "quiet")]
+    quiet: bool,
+}
+
+// zero runs would leave every phase without a timing, so turn it away up front
+fn parse_runs(text: &str) -> Result<usize, String> {
+    match text.parse::<usize>() {
+        Ok(0) => Err(String::from("needs at least one run")),
+        Ok(runs) => Ok(runs),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn print_available_days() {
+    eprintln!("Available days:");
+    for entry in registry::list() {
+        match entry.title {
+            Some(title) => eprintln!("  {:>2}: {}", entry.day, title),
+            None => eprintln!("  {:>2}", entry.day),
+        }
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_optional(value: &Option<String>) -> String {
+    match value {
+        Some(value) => json_string(value),
+        None => String::from("null"),
+    }
+}
+
+// big integers are sent as strings, since most JSON readers only keep 53 bits
+fn json_answer(answer: &Option<Answer>) -> String {
+    match answer {
+        None => String::from("null"),
+        Some(Answer::Int(val)) => val.to_string(),
+        Some(Answer::BigInt(val)) => json_string(&val.to_string()),
+        Some(Answer::Text(val)) => json_string(val),
+        Some(Answer::Lines(lines)) => {
+            let items: Vec<String> = lines.iter().map(|line| json_string(line)).collect();
+            format!("[{}]", items.join(","))
+        }
+    }
+}
+
+fn print_rows(format: Format, headers: &[&str], rows: &[Vec<String>]) {
+    match format {
+        Format::Json => {
+            // rows are built as JSON values already, so just pair them with their keys
+            for row in rows {
+                let fields: Vec<String> = headers
+                    .iter()
+                    .zip(row.iter())
+                    .map(|(key, value)| format!("{}:{}", json_string(key), value))
+                    .collect();
+                println!("{{{}}}", fields.join(","));
+            }
+        }
+        Format::Csv => {
+            println!("{}", headers.join(","));
+            for row in rows {
+                let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+                println!("{}", fields.join(","));
+            }
+        }
+        Format::Table => {
+            let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+            for row in rows {
+                for (col, cell) in row.iter().enumerate() {
+                    widths[col] = widths[col].max(cell.chars().count());
+                }
+            }
+            let print_row = |cells: Vec<&str>| {
+                let padded: Vec<String> = cells
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect();
+                println!("{}", padded.join("  ").trim_end());
+            };
+            print_row(headers.to_vec());
+            for row in rows {
+                print_row(row.iter().map(|cell| &cell[..]).collect());
+            }
+        }
+    }
+}
+
+fn format_duration(format: Format, duration: Duration) -> String {
+    match format {
+        Format::Table => format!("{:.2?}", duration),
+        Format::Csv | Format::Json => duration.as_nanos().to_string(),
+    }
+}
+
+fn print_results(format: Format, results: &[PartResult]) {
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|result| {
+            if let Format::Json = format {
+                return vec![
+                    result.day.to_string(),
+                    result.part.to_string(),
+                    json_answer(&result.answer),
+                    json_string(result.status.label()),
+                    format_duration(format, result.elapsed),
+                    json_optional(&result.error),
+                ];
+            }
+            let answer = match (&result.answer, &result.error) {
+                (Some(answer), _) => answer.to_string().replace('\n', " "),
+                (None, Some(error)) => error.replace('\n', " "),
+                (None, None) => String::from(""),
+            };
+            vec![
+                result.day.to_string(),
+                result.part.to_string(),
+                answer,
+                result.status.label().to_string(),
+                format_duration(format, result.elapsed),
+            ]
+        })
+        .collect();
+    let headers: &[&str] = match format {
+        Format::Table => &["Day", "Part", "Answer", "Status", "Time"],
+        Format::Csv => &["day", "part", "answer", "status", "elapsed_ns"],
+        Format::Json => &["day", "part", "answer", "status", "duration_ns", "error"],
+    };
+    print_rows(format, headers, &rows);
+}
+
+fn print_timings(format: Format, timings: &[PhaseTiming]) {
+    let rows: Vec<Vec<String>> = timings
+        .iter()
+        .map(|timing| {
+            let stat = |value: Option<Duration>| match (value, format) {
+                (Some(duration), _) => format_duration(format, duration),
+                (None, Format::Json) => String::from("null"),
+                (None, _) => String::from(""),
+            };
+            let (phase, error) = match format {
+                Format::Json if timing.skipped => {
+                    (json_string(timing.phase.label()), json_string("skipped"))
+                }
+                Format::Json => (
+                    json_string(timing.phase.label()),
+                    json_optional(&timing.error),
+                ),
+                _ if timing.skipped => (timing.phase.label().to_string(), String::from("skipped")),
+                _ => (
+                    timing.phase.label().to_string(),
+                    timing
+                        .error
+                        .as_deref()
+                        .unwrap_or_default()
+                        .replace('\n', " "),
+                ),
+            };
+            vec![
+                timing.day.to_string(),
+                phase,
+                timing.samples.len().to_string(),
+                stat(timing.min()),
+                stat(timing.median()),
+                stat(timing.max()),
+                error,
+            ]
+        })
+        .collect();
+    let headers = match format {
+        Format::Table => ["Day", "Phase", "Runs", "Min", "Median", "Max", "Error"],
+        Format::Csv | Format::Json => [
+            "day",
+            "phase",
+            "runs",
+            "min_ns",
+            "median_ns",
+            "max_ns",
+            "error",
+        ],
+    };
+    print_rows(format, &headers, &rows);
+}
+
+fn print_check(outcome: &CheckOutcome) {
+    let label = format!("Day {} part {}", outcome.result.day, outcome.result.part);
+    let actual = outcome.actual();
+    let expected = match &outcome.expected {
+        None => {
+            // nothing to compare with, but a part that errored still fails
+            let state = if outcome.passed() {
+                "unchecked"
+            } else {
+                "FAILED"
+            };
+            println!("{}: {} ({})", label, state, actual.replace('\n', " "));
+            return;
+        }
+        Some(expected) => expected,
+    };
+    if outcome.passed() {
+        println!("{}: ok ({})", label, actual.replace('\n', " "));
+        return;
+    }
+
+    // line by line diff so multi-line answers show exactly where they differ
+    println!("{}: FAILED", label);
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    for idx in 0..expected_lines.len().max(actual_lines.len()) {
+        match (expected_lines.get(idx), actual_lines.get(idx)) {
+            (Some(exp), Some(act)) if exp == act => println!("    {}", exp),
+            (exp, act) => {
+                if let Some(exp) = exp {
+                    println!("  - {}", exp);
+                }
+                if let Some(act) = act {
+                    println!("  + {}", act);
+                }
+            }
+        }
+    }
+}
+
+fn run_checks(days: &[(&SolverEntry, Input)], options: &[SolverOption]) -> bool {
+    let mut all_passed = true;
+    for (entry, input) in days {
+        let path = match input {
+            Input::File(path) => path,
+            Input::Stdin(_) => {
+                eprintln!(
+                    "Day {}: --check needs an input file to find its .answers file",
+                    entry.day
+                );
+                all_passed = false;
+                continue;
+            }
+        };
+        let answers = check::answers_path(path);
+        if !answers.exists() {
+            eprintln!(
+                "Day {}: no answers file at {}, skipping",
+                entry.day,
+                answers.display()
+            );
+            continue;
+        }
+        match check::check_file(entry, path, options) {
+            Ok(outcomes) => {
+                for outcome in &outcomes {
+                    print_check(outcome);
+                    all_passed &= outcome.passed();
+                }
+            }
+            Err(err) => {
+                eprintln!("Day {}: {}", entry.day, err);
+                all_passed = false;
+            }
+        }
+    }
+    all_passed
+}
+
+/// Where a day's input comes from.  Stdin is read up front, since it can only be read once
+enum Input {
+    File(PathBuf),
+    Stdin(String),
+}
+
+fn inputs_root(args: &Opt) -> PathBuf {
+    if let Some(root) = &args.inputs {
+        return root.to_path_buf();
+    }
+    INPUTS_ROOTS
+        .iter()
+        .map(PathBuf::from)
+        .find(|root| root.is_dir())
+        .unwrap_or_else(|| PathBuf::from(INPUTS_ROOTS[0]))
+}
+
+fn default_input(args: &Opt, day: usize) -> Input {
+    let file_name = if args.test { "test.txt" } else { "input.txt" };
+    Input::File(runner::input_path(&inputs_root(args), day, file_name))
+}
+
+// with --all, a day that has no input file is skipped rather than failed
+fn missing_input<'a>(args: &Opt, input: &'a Input) -> Option<&'a Path> {
+    match input {
+        Input::File(path) if args.all && !path.exists() => Some(path),
+        _ => None,
+    }
+}
+
+// --input if it was given, otherwise the default input for the day
+fn day_input(args: &Opt, day: usize) -> Result<Input, Box<dyn Error>> {
+    Ok(match &args.input {
+        Some(path) if path == Path::new("-") => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            Input::Stdin(content)
+        }
+        Some(path) => Input::File(path.to_path_buf()),
+        None => default_input(args, day),
+    })
+}
+
+fn selected_days(args: &Opt) -> Result<Vec<(&'static SolverEntry, Input)>, Box<dyn Error>> {
+    if args.all {
+        return Ok(registry::list()
+            .iter()
+            .map(|entry| (entry, default_input(args, entry.day)))
+            .collect());
+    }
+
+    let entry = match args.day.parse().ok().and_then(registry::get) {
+        Some(entry) => entry,
+        None => {
             eprintln!("Day {} is not implemented yet", &args.day);
+            print_available_days();
             process::exit(1);
         }
     };
+    Ok(vec![(entry, day_input(args, entry.day)?)])
+}
 
-    println!("Part 1: {}", day.part_one()?);
-    println!("Part 2: {}", day.part_two()?);
+fn read_input(input: &Input) -> Result<String, Box<dyn Error>> {
+    match input {
+        Input::File(path) => Ok(std::fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?),
+        Input::Stdin(content) => Ok(content.clone()),
+    }
+}
+
+// overrides from the file go first so that ones given on the command line win, and both
+// come before -o so that e.g. start=N can name a rule that only an override defines
+fn solver_options(args: &Opt) -> Result<Vec<SolverOption>, Box<dyn Error>> {
+    let mut options = vec![];
+    if let Some(path) = &args.overrides_file {
+        let rules = std::fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+        options.push(SolverOption::new("override", &rules));
+    }
+    for rule in &args.overrides {
+        options.push(SolverOption::new("override", rule));
+    }
+    options.extend(args.options.iter().cloned());
+    Ok(options)
+}
 
+fn log_level(args: &Opt) -> Option<Level> {
+    if args.quiet {
+        return None;
+    }
+    Some(match args.verbose {
+        0 => Level::Error,
+        1 => Level::Info,
+        2 => Level::Debug,
+        _ => Level::Trace,
+    })
+}
+
+// the grammar in the day 19 input, with any overrides from the command line applied
+fn load_grammar(args: &Opt) -> Result<(Grammar, Vec<String>), Box<dyn Error>> {
+    let content = read_input(&day_input(args, 19)?)?;
+    let (rules, messages) = grammar::split_input(&content);
+    let mut grammar = Grammar::from_input(&rules)?;
+    for option in solver_options(args)? {
+        if !grammar.apply_option(&option.name, &option.value)? {
+            return Err(format!("unknown option '{}'", option.name).into());
+        }
+    }
+    Ok((grammar, messages))
+}
+
+fn print_derivations(grammar: &Grammar, messages: &[String], dot: bool, limit: usize) {
+    let parser = Parser::new(grammar);
+    for message in messages {
+        let trees = parser.derivations(message, limit);
+        let failure = if trees.is_empty() {
+            parser.diagnose(message)
+        } else {
+            None
+        };
+        if dot {
+            if let Some(failure) = &failure {
+                println!("// {:?}: {}", message, failure);
+            }
+            for (idx, tree) in trees.iter().enumerate() {
+                print!("{}", tree.to_dot(&format!("{} #{}", message, idx + 1)));
+            }
+            continue;
+        }
+        match trees.len() {
+            0 => match &failure {
+                Some(failure) => println!("{:?}: {}", message, failure),
+                None => println!("{:?}: no derivations", message),
+            },
+            1 => println!("{:?}: 1 derivation", message),
+            n => println!("{:?}: {} derivations", message, n),
+        }
+        for tree in &trees {
+            print!("{}", tree);
+        }
+        println!();
+    }
+}
+
+fn run_grammar(args: &Opt, command: &GrammarCommand) -> Result<(), Box<dyn Error>> {
+    let (grammar, input_messages) = load_grammar(args)?;
+    match command {
+        GrammarCommand::Check => {
+            let warnings = grammar.validate();
+            for warning in &warnings {
+                println!("warning: {}", warning);
+            }
+            if !warnings.is_empty() {
+                process::exit(1);
+            }
+            println!(
+                "{} rules, all reachable from rule {}",
+                grammar.rules.len(),
+                grammar.start_symbol
+            );
+        }
+        GrammarCommand::Parse {
+            dot,
+            limit,
+            messages,
+        } => {
+            let messages = if messages.is_empty() {
+                &input_messages
+            } else {
+                messages
+            };
+            print_derivations(&grammar, messages, *dot, *limit);
+        }
+        GrammarCommand::Lengths => {
+            let ranges = language::length_ranges(&grammar);
+            let mut symbols: Vec<&String> = grammar.rules.keys().collect();
+            symbols.sort_by_key(|symbol| (symbol.len(), symbol.to_string()));
+            for symbol in symbols {
+                match ranges.get(symbol) {
+                    Some(range) => println!("{}: {}", symbol, range),
+                    None => println!("{}: matches nothing", symbol),
+                }
+            }
+        }
+        GrammarCommand::Count { max_length, rule } => {
+            let symbol = language_rule(&grammar, rule)?;
+            match language::count(&grammar, symbol, *max_length) {
+                Some(count) => println!("{}", count),
+                None => println!("infinitely many, try --max-len"),
+            }
+        }
+        GrammarCommand::Enumerate {
+            max_length,
+            limit,
+            rule,
+        } => {
+            let symbol = language_rule(&grammar, rule)?;
+            let strings = language::enumerate(&grammar, symbol, *max_length);
+            for string in strings.take(limit.unwrap_or(usize::MAX)) {
+                println!("{}", string);
+            }
+        }
+    }
     Ok(())
 }
+
+// the rule asked for on the command line, or the start rule
+fn language_rule<'a>(grammar: &'a Grammar, rule: &'a Option<String>) -> Result<&'a str, String> {
+    let symbol = rule.as_ref().unwrap_or(&grammar.start_symbol);
+    if grammar.rules.contains_key(symbol) {
+        Ok(symbol)
+    } else {
+        Err(format!("rule {} is not defined", symbol))
+    }
+}
+
+fn run_image(
+    args: &Opt,
+    format: Option<ImageFormat>,
+    keep_borders: bool,
+    tile_ids: bool,
+    output: &Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let format = format
+        .or_else(|| output.as_deref().and_then(ImageFormat::from_path))
+        .unwrap_or(ImageFormat::Ascii);
+    if tile_ids && format != ImageFormat::Ascii {
+        return Err("tile ids can only be written over ascii output".into());
+    }
+
+    let content = read_input(&day_input(args, 20)?)?;
+    let mut solver = DayTwentySolver::from_input(&content)?;
+    for option in solver_options(args)? {
+        if !solver.set_option(&option.name, &option.value)? {
+            return Err(format!("day 20 has no option '{}'", option.name).into());
+        }
+    }
+    let layout = solver.assemble()?;
+    let export = Export {
+        format,
+        keep_borders,
+        tile_ids,
+    };
+    let picture = image::export(&layout, solver.border(), &export);
+    match output {
+        Some(path) => std::fs::write(path, picture)
+            .map_err(|err| format!("could not write {}: {}", path.display(), err))?,
+        None => print!("{}", picture),
+    }
+    Ok(())
+}
+
+fn run(args: Opt) -> Result<(), Box<dyn Error>> {
+    log::set_max_level(log_level(&args));
+    match &args.command {
+        Some(Command::Grammar(command)) => return run_grammar(&args, command),
+        Some(Command::Image {
+            format,
+            borders,
+            ids,
+            output,
+        }) => return run_image(&args, *format, *borders, *ids, output),
+        None => (),
+    }
+    let days = selected_days(&args)?;
+    let options = solver_options(&args)?;
+    let format = args.format.unwrap_or(Format::Table);
+
+    if args.check {
+        if !run_checks(&days, &options) {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(runs) = args.bench {
+        let mut timings = vec![];
+        for (entry, input) in &days {
+            if missing_input(&args, input).is_some() {
+                timings.extend(bench::skipped_day(entry.day));
+                continue;
+            }
+            timings.extend(match input {
+                Input::File(path) => bench::bench_day_file(entry, path, &options, runs),
+                Input::Stdin(content) => bench::bench_day(entry, content, &options, runs),
+            });
+        }
+        print_timings(format, &timings);
+        if timings.iter().any(|timing| timing.error.is_some()) {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.all || args.format.is_some() {
+        let mut results = vec![];
+        for (entry, input) in &days {
+            if let Some(path) = missing_input(&args, input) {
+                results.extend(runner::skipped_day(entry.day, path));
+                continue;
+            }
+            results.extend(match input {
+                Input::File(path) => runner::run_day_file(entry, path, &options),
+                Input::Stdin(content) => runner::run_day(entry, content, &options),
+            });
+        }
+        print_results(format, &results);
+        if results.iter().any(|result| result.status == Status::Error) {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // load the input
+    let (entry, input) = &days[0];
+    let content = read_input(input)?;
+
+    let day = entry.build_with(&content, &options)?;
+
+    print_part(1, day.part_one())?;
+    print_part(2, day.part_two())?;
+
+    Ok(())
+}
+
+// like the runner, a part that isn't implemented or doesn't apply to the input isn't a failure
+fn print_part(part: usize, result: Result<Answer, SolveError>) -> Result<(), SolveError> {
+    match result {
+        Ok(answer) => println!("Part {}: {}", part, answer),
+        Err(SolveError::NotImplemented) => println!("Part {}: not implemented", part),
+        Err(SolveError::NotApplicable(reason)) => println!("Part {}: skipped, {}", part, reason),
+        Err(err) => return Err(err),
+    }
+    Ok(())
+}
+
+fn main() {
+    // errors carry their own context, so show them with Display rather than Debug
+    if let Err(err) = run(Opt::from_args()) {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,141 @@
+use super::day_nineteen::DayNineteenSolver;
+use super::day_one::DayOneSolver;
+use super::day_three::DayThreeSolver;
+use super::day_twenty::DayTwentySolver;
+use super::day_twentyfive::DayTwentyFiveSolver;
+use super::day_twentyfour::DayTwentyFourSolver;
+use super::day_twentyone::DayTwentyOneSolver;
+use super::day_twentythree::DayTwentyThreeSolver;
+use super::day_twentytwo::DayTwentyTwoSolver;
+use super::day_two::DayTwoSolver;
+use super::{ParseError, Solver};
+use std::str::FromStr;
+
+/// A `name=value` setting handed to a solver after it is built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverOption {
+    pub name: String,
+    pub value: String,
+}
+
+impl SolverOption {
+    pub fn new(name: &str, value: &str) -> SolverOption {
+        SolverOption {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl FromStr for SolverOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<SolverOption, String> {
+        match s.find('=') {
+            Some(idx) => Ok(SolverOption::new(s[..idx].trim(), &s[idx + 1..])),
+            None => Err(format!("expected name=value, got '{}'", s)),
+        }
+    }
+}
+
+type Constructor = fn(&str) -> Result<Box<dyn Solver>, ParseError>;
+
+/// A single day that the binary knows how to solve
+pub struct SolverEntry {
+    pub day: usize,
+    pub title: Option<&'static str>,
+    constructor: Constructor,
+}
+
+impl SolverEntry {
+    /// Parse the input and build the solver for this day
+    pub fn build(&self, input: &str) -> Result<Box<dyn Solver>, ParseError> {
+        (self.constructor)(input)
+    }
+
+    /// Build the solver, then apply each option in order
+    pub fn build_with(
+        &self,
+        input: &str,
+        options: &[SolverOption],
+    ) -> Result<Box<dyn Solver>, ParseError> {
+        let mut solver = self.build(input)?;
+        for option in options {
+            if !solver.set_option(&option.name, &option.value)? {
+                return Err(ParseError::input(
+                    self.day,
+                    format!("unknown option '{}'", option.name),
+                ));
+            }
+        }
+        Ok(solver)
+    }
+}
+
+fn construct<S: Solver + 'static>(input: &str) -> Result<Box<dyn Solver>, ParseError> {
+    Ok(S::from_input(input)?)
+}
+
+// Adding a day means adding its module to lib.rs and an entry here, in day order
+static SOLVERS: &[SolverEntry] = &[
+    SolverEntry {
+        day: 1,
+        title: Some("Report Repair"),
+        constructor: construct::<DayOneSolver>,
+    },
+    SolverEntry {
+        day: 2,
+        title: Some("Password Philosophy"),
+        constructor: construct::<DayTwoSolver>,
+    },
+    SolverEntry {
+        day: 3,
+        title: Some("Toboggan Trajectory"),
+        constructor: construct::<DayThreeSolver>,
+    },
+    SolverEntry {
+        day: 19,
+        title: Some("Monster Messages"),
+        constructor: construct::<DayNineteenSolver>,
+    },
+    SolverEntry {
+        day: 20,
+        title: Some("Jurassic Jigsaw"),
+        constructor: construct::<DayTwentySolver>,
+    },
+    SolverEntry {
+        day: 21,
+        title: Some("Allergen Assessment"),
+        constructor: construct::<DayTwentyOneSolver>,
+    },
+    SolverEntry {
+        day: 22,
+        title: Some("Crab Combat"),
+        constructor: construct::<DayTwentyTwoSolver>,
+    },
+    SolverEntry {
+        day: 23,
+        title: Some("Crab Cups"),
+        constructor: construct::<DayTwentyThreeSolver>,
+    },
+    SolverEntry {
+        day: 24,
+        title: Some("Lobby Layout"),
+        constructor: construct::<DayTwentyFourSolver>,
+    },
+    SolverEntry {
+        day: 25,
+        title: Some("Combo Breaker"),
+        constructor: construct::<DayTwentyFiveSolver>,
+    },
+];
+
+/// All registered days, sorted by day number
+pub fn list() -> &'static [SolverEntry] {
+    SOLVERS
+}
+
+/// Look up the solver registered for a day
+pub fn get(day: usize) -> Option<&'static SolverEntry> {
+    SOLVERS.iter().find(|entry| entry.day == day)
+}
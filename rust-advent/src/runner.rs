@@ -0,0 +1,145 @@
+use super::registry::{SolverEntry, SolverOption};
+use super::{Answer, SolveError, Solver};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error,
+    NotImplemented,
+    Skipped,
+}
+
+impl Status {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Error => "error",
+            Status::NotImplemented => "not implemented",
+            Status::Skipped => "skipped",
+        }
+    }
+}
+
+/// The outcome of running one part of one day
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub day: usize,
+    pub part: usize,
+    pub answer: Option<Answer>,
+    pub error: Option<String>,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+impl PartResult {
+    fn failed(day: usize, part: usize, error: String) -> PartResult {
+        PartResult {
+            day,
+            part,
+            answer: None,
+            error: Some(error),
+            status: Status::Error,
+            elapsed: Duration::default(),
+        }
+    }
+}
+
+// if the input can't be read or parsed, neither part gets to run
+fn failed_day(day: usize, error: String) -> Vec<PartResult> {
+    vec![
+        PartResult::failed(day, 1, error.clone()),
+        PartResult::failed(day, 2, error),
+    ]
+}
+
+/// Both parts of a day that has no input file at `path`, which isn't counted as a failure
+pub fn skipped_day(day: usize, path: &Path) -> Vec<PartResult> {
+    let error = format!("no input at {}", path.display());
+    [1, 2]
+        .iter()
+        .map(|part| PartResult {
+            status: Status::Skipped,
+            ..PartResult::failed(day, *part, error.clone())
+        })
+        .collect()
+}
+
+/// Where an input file for a day lives under the inputs root, e.g. `<root>/20/test.txt`
+pub fn input_path(root: &Path, day: usize, file_name: &str) -> PathBuf {
+    root.join(day.to_string()).join(file_name)
+}
+
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        format!("panicked: {}", message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        format!("panicked: {}", message)
+    } else {
+        String::from("panicked")
+    }
+}
+
+fn run_part(day: usize, part: usize, solver: &dyn Solver) -> PartResult {
+    let start = Instant::now();
+    // a panicking solver should only fail its own row, not the whole run
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+        if part == 1 {
+            solver.part_one()
+        } else {
+            solver.part_two()
+        }
+    }));
+    let elapsed = start.elapsed();
+
+    let (answer, error, status) = match outcome {
+        Ok(Ok(answer)) => (Some(answer), None, Status::Ok),
+        Ok(Err(SolveError::NotImplemented)) => (
+            None,
+            Some(SolveError::NotImplemented.to_string()),
+            Status::NotImplemented,
+        ),
+        Ok(Err(err @ SolveError::NotApplicable(_))) => {
+            (None, Some(err.to_string()), Status::Skipped)
+        }
+        Ok(Err(err)) => (None, Some(err.to_string()), Status::Error),
+        Err(payload) => (None, Some(panic_message(payload)), Status::Error),
+    };
+    PartResult {
+        day,
+        part,
+        answer,
+        error,
+        status,
+        elapsed,
+    }
+}
+
+/// Build the solver for a day from its input and run both parts
+pub fn run_day(entry: &SolverEntry, input: &str, options: &[SolverOption]) -> Vec<PartResult> {
+    let built = panic::catch_unwind(AssertUnwindSafe(|| entry.build_with(input, options)));
+    let solver = match built {
+        Ok(Ok(solver)) => solver,
+        Ok(Err(err)) => return failed_day(entry.day, err.to_string()),
+        Err(payload) => return failed_day(entry.day, panic_message(payload)),
+    };
+
+    vec![
+        run_part(entry.day, 1, solver.as_ref()),
+        run_part(entry.day, 2, solver.as_ref()),
+    ]
+}
+
+/// Same as `run_day`, but reads the input from a file first
+pub fn run_day_file(entry: &SolverEntry, path: &Path, options: &[SolverOption]) -> Vec<PartResult> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => run_day(entry, &content, options),
+        Err(err) => failed_day(
+            entry.day,
+            format!("could not read {}: {}", path.display(), err),
+        ),
+    }
+}
@@ -0,0 +1,210 @@
+use advent2020::bench;
+use advent2020::check::{self, ExpectedAnswers};
+use advent2020::day_twentyfive;
+use advent2020::registry::{self, SolverOption};
+use advent2020::runner::{self, Status};
+use advent2020::{Answer, ParseError};
+use std::path::PathBuf;
+
+fn inputs_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../inputs")
+}
+
+fn assert_answers(day: usize, name: &str) {
+    assert_answers_with(day, name, &[]);
+}
+
+fn assert_answers_with(day: usize, name: &str, options: &[SolverOption]) {
+    let entry = registry::get(day).expect("day is not registered");
+    let path = inputs_root().join(day.to_string()).join(name);
+    let outcomes = check::check_file(entry, &path, options).unwrap();
+    for outcome in outcomes {
+        assert!(
+            outcome.passed(),
+            "day {} part {}: expected {:?}, got {}",
+            day,
+            outcome.result.part,
+            outcome.expected,
+            outcome.actual()
+        );
+    }
+}
+
+#[test]
+fn day_three_map_without_columns() {
+    let entry = registry::get(3).unwrap();
+    let results = runner::run_day(entry, "\n", &[]);
+    assert_eq!(results[0].status, Status::Error);
+    assert!(results[0].error.as_ref().unwrap().contains("no columns"));
+}
+
+#[test]
+fn unchecked_parts_that_error_do_not_pass() {
+    let entry = registry::get(3).unwrap();
+    let expected = ExpectedAnswers::parse("part1: 0").unwrap();
+    let outcomes = check::check_day(entry, "x", &[], &expected);
+    assert!(outcomes.iter().all(|outcome| !outcome.passed()));
+
+    // a skipped part has nothing to check either, but it isn't a failure
+    let entry = registry::get(19).unwrap();
+    let input = std::fs::read_to_string(inputs_root().join("19").join("test.txt")).unwrap();
+    let outcomes = check::check_day(entry, &input, &[], &ExpectedAnswers::parse("").unwrap());
+    assert!(outcomes[1].expected.is_none());
+    assert!(outcomes[1].passed());
+}
+
+#[test]
+fn hand_built_errors_without_a_column_still_display() {
+    let err = ParseError {
+        day: 3,
+        line: 1,
+        column: 0,
+        text: String::from("..#"),
+        message: String::from("bad row"),
+    };
+    assert!(err.to_string().ends_with(" | ^"));
+}
+
+#[test]
+fn day_nineteen_test() {
+    assert_answers(19, "test.txt");
+}
+
+#[test]
+fn day_nineteen_test_part2() {
+    assert_answers(19, "test_part2.txt");
+}
+
+#[test]
+fn day_nineteen_part_two_needs_the_looping_rules() {
+    let entry = registry::get(19).unwrap();
+    let path = inputs_root().join("19").join("test.txt");
+    let results = runner::run_day_file(entry, &path, &[]);
+    assert_eq!(results[1].status, Status::Skipped);
+    assert_eq!(
+        results[1].error.as_deref(),
+        Some("Skipped: part two needs rules 8, 11, 42 and 31, but 8, 11, 42, 31 are missing")
+    );
+}
+
+#[test]
+fn day_nineteen_test_literals() {
+    assert_answers(19, "test_literals.txt");
+}
+
+#[test]
+fn day_nineteen_cyk() {
+    let options = [SolverOption::new("parser", "cyk")];
+    assert_answers_with(19, "test_part2.txt", &options);
+    assert_answers_with(19, "test_literals.txt", &options);
+}
+
+#[test]
+fn day_nineteen_overrides() {
+    // undo the looping rules that test_part2.txt ships with
+    let entry = registry::get(19).unwrap();
+    let path = inputs_root().join("19").join("test_part2.txt");
+    let options = [SolverOption::new("override", "8: 42\n11: 42 31")];
+    let results = runner::run_day_file(entry, &path, &options);
+    assert_eq!(results[0].answer, Some(Answer::Int(3)));
+    assert_eq!(results[1].answer, Some(Answer::Int(12)));
+}
+
+#[test]
+fn day_twenty_test() {
+    assert_answers(20, "test.txt");
+}
+
+#[test]
+fn day_twenty_other_tile_sizes() {
+    assert_answers(20, "test_12.txt");
+}
+
+#[test]
+fn day_twenty_borders() {
+    let entry = registry::get(20).unwrap();
+    let path = inputs_root().join("20").join("test_12.txt");
+    // the sea monster only survives with the usual one cell border stripped
+    for (border, roughness) in [("0", 387), ("2", 164)] {
+        let options = [SolverOption::new("border", border)];
+        let results = runner::run_day_file(entry, &path, &options);
+        assert_eq!(results[1].answer, Some(Answer::Int(roughness)));
+    }
+
+    let options = [SolverOption::new("border", "6")];
+    let results = runner::run_day_file(entry, &path, &options);
+    assert!(results[0].error.as_ref().unwrap().contains("border"));
+}
+
+#[test]
+fn day_twenty_decoys() {
+    // a 4x3 picture plus two tiles that each share an edge with it
+    assert_answers(20, "test_decoys.txt");
+    assert_answers_with(20, "test_decoys.txt", &[SolverOption::new("layout", "3x4")]);
+
+    let entry = registry::get(20).unwrap();
+    let path = inputs_root().join("20").join("test_decoys.txt");
+    let results = runner::run_day_file(entry, &path, &[SolverOption::new("layout", "7x2")]);
+    assert_eq!(
+        results[0].error.as_deref(),
+        Some("No solution found: no 7x2 arrangement of the 14 tiles fits together")
+    );
+}
+
+#[test]
+fn day_twenty_patterns() {
+    let monster = inputs_root().join("20").join("sea_monster.txt");
+    let options = [SolverOption::new("pattern", monster.to_str().unwrap())];
+    assert_answers_with(20, "test.txt", &options);
+}
+
+#[test]
+fn day_twentyone_test() {
+    assert_answers(21, "test.txt");
+}
+
+#[test]
+fn day_twentytwo_test() {
+    assert_answers(22, "test.txt");
+}
+
+#[test]
+fn day_twentythree_test() {
+    assert_answers(23, "test.txt");
+}
+
+#[test]
+fn day_twentyfour_test() {
+    assert_answers(24, "test.txt");
+}
+
+#[test]
+fn day_twentyfive_test() {
+    assert_answers(25, "test.txt");
+}
+
+#[test]
+fn day_twentyfive_custom_modulus() {
+    assert_answers(25, "test_modulus.txt");
+}
+
+#[test]
+fn day_twentyfive_oversized_modulus() {
+    let entry = registry::get(25).unwrap();
+    let input = "5764801\n17807724\nsubject: 2\nmodulus: 1000000000000000003\n";
+    let results = runner::run_day(entry, input, &[]);
+    assert!(results[0].error.as_ref().unwrap().contains("too big"));
+
+    assert_eq!(day_twentyfive::modpow(2, 3, 0), 0);
+    assert_eq!(day_twentyfive::discrete_log(2, 3, 0), None);
+}
+
+#[test]
+fn unimplemented_parts_are_skipped_when_benchmarking() {
+    let entry = registry::get(25).unwrap();
+    let path = inputs_root().join("25").join("test.txt");
+    let timings = bench::bench_day_file(entry, &path, &[], 2);
+    assert_eq!(timings[1].samples.len(), 2);
+    assert!(timings[2].skipped);
+    assert_eq!(timings[2].error, None);
+}
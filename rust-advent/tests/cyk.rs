@@ -0,0 +1,104 @@
+use advent2020::day_nineteen::cyk::Cnf;
+use advent2020::day_nineteen::earley::Parser;
+use advent2020::day_nineteen::grammar::Grammar;
+use advent2020::day_nineteen::language;
+
+// xorshift64, so the same grammars come up every run without pulling in a crate
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+const LITERALS: &[&str] = &["a", "b", "ab", "ba", "aab"];
+
+// a few rules using each other at random: recursive, empty, looping on themselves or
+// matching nothing at all are all fair game
+fn random_grammar(rng: &mut Rng) -> String {
+    let rules = 2 + rng.below(5);
+    let mut lines = vec![];
+    for symbol in 0..rules {
+        if rng.below(3) == 0 {
+            lines.push(format!(
+                "{}: \"{}\"",
+                symbol,
+                LITERALS[rng.below(LITERALS.len())]
+            ));
+            continue;
+        }
+        let alternatives: Vec<String> = (0..1 + rng.below(3))
+            .map(|_| {
+                let used: Vec<String> = (0..rng.below(4))
+                    .map(|_| rng.below(rules).to_string())
+                    .collect();
+                if used.is_empty() {
+                    String::from("\"\"")
+                } else {
+                    used.join(" ")
+                }
+            })
+            .collect();
+        lines.push(format!("{}: {}", symbol, alternatives.join(" | ")));
+    }
+    lines.join("\n")
+}
+
+fn random_message(rng: &mut Rng) -> String {
+    (0..rng.below(8))
+        .map(|_| if rng.below(2) == 0 { 'a' } else { 'b' })
+        .collect()
+}
+
+#[test]
+fn cyk_agrees_with_earley_on_random_grammars() {
+    let mut rng = Rng(0x2020_1219);
+    for _ in 0..300 {
+        let rules = random_grammar(&mut rng);
+        let grammar = Grammar::from_input(&rules).unwrap();
+        let parser = Parser::new(&grammar);
+        let cnf = Cnf::new(&grammar);
+
+        // random messages mostly fail, so mix in some that have to match
+        let matching: Vec<String> = language::enumerate(&grammar, "0", Some(7))
+            .take(10)
+            .collect();
+        for message in &matching {
+            assert!(cnf.recognize(message), "{:?} against\n{}", message, rules);
+        }
+        let mut messages: Vec<String> = (0..20).map(|_| random_message(&mut rng)).collect();
+        messages.extend(matching);
+        for message in &messages {
+            assert_eq!(
+                cnf.recognize(message),
+                parser.recognize(message),
+                "{:?} against\n{}",
+                message,
+                rules
+            );
+        }
+    }
+}
+
+#[test]
+fn cyk_handles_long_literals_and_the_empty_message() {
+    let grammar = Grammar::from_input("0: 1 2\n1: \"λab\"\n2: 1 | \"\"").unwrap();
+    let cnf = Cnf::new(&grammar);
+    assert!(cnf.recognize("λab"));
+    assert!(cnf.recognize("λabλab"));
+    assert!(!cnf.recognize("λa"));
+    assert!(!cnf.recognize(""));
+
+    let nullable = Grammar::from_input("0: 1 1\n1: \"\" | 2\n2: \"a\"").unwrap();
+    assert!(Cnf::new(&nullable).recognize(""));
+    assert!(Cnf::new(&nullable).recognize("aa"));
+    assert!(!Cnf::new(&nullable).recognize("aaa"));
+}
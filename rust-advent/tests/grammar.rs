@@ -0,0 +1,221 @@
+use advent2020::day_nineteen::earley;
+use advent2020::day_nineteen::grammar::{Grammar, GrammarWarning};
+use advent2020::day_nineteen::language::{self, LengthRange};
+use advent2020::day_nineteen::pattern;
+
+const AMBIGUOUS: &str = "0: 1 | 2\n1: 3 3\n2: 4\n3: \"a\"\n4: \"aa\"";
+
+#[test]
+fn derivations_cover_the_message() {
+    let grammar = Grammar::from_input("0: 1 2\n1: \"ab\"\n2: 1 | 3\n3: \"c\"").unwrap();
+    let trees = earley::derivations(&grammar, "abc", 10);
+    assert_eq!(trees.len(), 1);
+    assert_eq!(trees[0].text(), "abc");
+    assert!(earley::derivations(&grammar, "abca", 10).is_empty());
+}
+
+#[test]
+fn ambiguous_derivations_respect_the_limit() {
+    let grammar = Grammar::from_input(AMBIGUOUS).unwrap();
+    assert_eq!(earley::derivations(&grammar, "aa", 10).len(), 2);
+    assert_eq!(earley::derivations(&grammar, "aa", 1).len(), 1);
+}
+
+#[test]
+fn dot_output_escapes_labels() {
+    let grammar = Grammar::from_input("0: 1\n1: \"say \"hi\"\"").unwrap();
+    let trees = earley::derivations(&grammar, "say \"hi\"", 1);
+    let dot = trees[0].to_dot("quotes");
+    assert!(dot.starts_with("digraph \"quotes\" {"));
+    assert!(dot.contains(r#"[label="say \"hi\"", shape=box]"#));
+}
+
+#[test]
+fn failures_point_at_the_furthest_column() {
+    let grammar = Grammar::from_input(
+        "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"",
+    )
+    .unwrap();
+    assert_eq!(earley::diagnose(&grammar, "ababbb"), None);
+
+    let failure = earley::diagnose(&grammar, "aaabbb").unwrap();
+    assert_eq!(
+        failure.to_string(),
+        "unexpected 'b' at column 5, expected 'a'"
+    );
+    let failure = earley::diagnose(&grammar, "abx").unwrap();
+    assert_eq!(
+        failure.to_string(),
+        "unexpected 'x' at column 3, expected 'a' or 'b'"
+    );
+    let failure = earley::diagnose(&grammar, "aaaabbb").unwrap();
+    assert_eq!(
+        failure.to_string(),
+        "unexpected 'b' at column 7, expected end of input"
+    );
+    let failure = earley::diagnose(&grammar, "ababb").unwrap();
+    assert_eq!(failure.found, None);
+    assert_eq!(failure.expected, vec!["b"]);
+}
+
+#[test]
+fn validation_finds_unreachable_rules_and_extra_roots() {
+    let mut grammar =
+        Grammar::from_input("0: 1 0 | 1\n1: \"a\"\n2: 3\n3: \"b\"\n4: \"c\"").unwrap();
+    assert_eq!(
+        grammar.validate(),
+        vec![
+            GrammarWarning::Unreachable(vec!["2".into(), "3".into(), "4".into()]),
+            GrammarWarning::MultipleRoots(vec!["0".into(), "2".into(), "4".into()]),
+        ]
+    );
+
+    grammar.apply_overrides("0: 1 0 | 2 | 4").unwrap();
+    assert_eq!(grammar.validate(), vec![]);
+}
+
+#[test]
+fn start_rule_can_be_chosen() {
+    let mut grammar = Grammar::from_input("0: 1\n1: \"a\"\n2: \"b\"").unwrap();
+    assert!(earley::recognize(&grammar, "a"));
+    grammar.set_start("2").unwrap();
+    assert!(earley::recognize(&grammar, "b"));
+    assert!(grammar.set_start("7").is_err());
+    assert!(Grammar::from_input("1: \"a\"").is_err());
+}
+
+#[test]
+fn undefined_rules_are_all_named() {
+    let err = Grammar::from_input("0: 1 2 3\n1: \"a\"").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 6));
+    assert_eq!(err.message, "rule 2 is never defined (nor are 3)");
+}
+
+#[test]
+fn empty_alternatives_match_nothing() {
+    let grammar = Grammar::from_input("0: 4 5\n4: \"a\"\n5: 4 5 | \"\"").unwrap();
+    for message in &["a", "aa", "aaaa"] {
+        assert!(earley::recognize(&grammar, message), "{}", message);
+    }
+    assert!(!earley::recognize(&grammar, ""));
+    assert!(!earley::recognize(&grammar, "ab"));
+    assert_eq!(earley::derivations(&grammar, "aa", 10).len(), 1);
+}
+
+#[test]
+fn nullable_rules_complete_in_the_set_they_start_in() {
+    // without the nullable fix, 0 never sees the second 1 complete
+    let grammar = Grammar::from_input("0: 1 1 2\n1: 3 | \"\"\n2: \"x\"\n3: \"\"").unwrap();
+    assert!(earley::recognize(&grammar, "x"));
+    let grammar = Grammar::from_input("0: 1 1\n1: \"\"").unwrap();
+    assert!(earley::recognize(&grammar, ""));
+}
+
+#[test]
+fn empty_alternatives_have_to_be_spelled_out() {
+    let err = Grammar::from_input("0: 1 |\n1: \"a\"").unwrap_err();
+    assert_eq!(err.column, 7);
+    assert!(Grammar::from_input("0: 1 \"b\"\n1: \"a\"").is_err());
+}
+
+#[test]
+fn quoted_alternatives_are_not_one_terminal() {
+    let err = Grammar::from_input("0: 1\n1: \"a\" | \"\"").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 4));
+    assert!(err.message.contains("rule of their own"), "{}", err.message);
+    assert!(Grammar::from_input("0: 1\n1: \"a\" \"b\"").is_err());
+    // literals can still hold spaces, pipes and quotes
+    for literal in &["bar baz", "a|b", "say \"hi\""] {
+        let grammar = Grammar::from_input(&format!("0: \"{}\"", literal)).unwrap();
+        assert!(earley::recognize(&grammar, literal), "{}", literal);
+    }
+}
+
+#[test]
+fn only_acyclic_grammars_become_regexes() {
+    let grammar = Grammar::from_input(
+        "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"",
+    )
+    .unwrap();
+    let regex = pattern::compile(&grammar).unwrap();
+    for message in &["ababbb", "abbbab", "bababa", "aaabbb", "aaaabbb"] {
+        assert_eq!(
+            regex.is_match(message),
+            earley::recognize(&grammar, message),
+            "{}",
+            message
+        );
+    }
+
+    let recursive = Grammar::from_input("0: 1 0 | 1\n1: \"a\"").unwrap();
+    assert!(pattern::is_recursive(&recursive));
+    assert!(pattern::compile(&recursive).is_none());
+}
+
+#[test]
+fn deep_grammars_are_too_big_for_a_regex() {
+    // every level doubles the pattern, so spelling out all 2^40 letters has to be cut short
+    let mut rules: Vec<String> = (0..40)
+        .map(|i| format!("{}: {} {}", i, i + 1, i + 1))
+        .collect();
+    rules.push(String::from("40: \"a\""));
+    let grammar = Grammar::from_input(&rules.join("\n")).unwrap();
+    assert!(!pattern::is_recursive(&grammar));
+    assert!(pattern::compile(&grammar).is_none());
+    assert!(!earley::recognize(&grammar, "aaaa"));
+}
+
+#[test]
+fn regexes_escape_literals_and_allow_empty_alternatives() {
+    let grammar = Grammar::from_input("0: 1 2\n1: \"a.b\"\n2: 3 | \"\"\n3: \"λ+\"").unwrap();
+    let regex = pattern::compile(&grammar).unwrap();
+    assert!(regex.is_match("a.b"));
+    assert!(regex.is_match("a.bλ+"));
+    assert!(!regex.is_match("axb"));
+    assert!(!regex.is_match("a.bλλ"));
+}
+
+#[test]
+fn length_ranges_handle_loops_and_empty_rules() {
+    let grammar = Grammar::from_input(
+        "0: 1 2\n1: 1 | 3\n2: 3 2 | \"\"\n3: \"ab\"\n4: 4 3\n5: 5 6 | 3\n6: \"\"",
+    )
+    .unwrap();
+    let ranges = language::length_ranges(&grammar);
+    let range = |min, max| Some(LengthRange { min, max });
+    assert_eq!(ranges.get("0").copied(), range(2, None));
+    // going round 1 -> 1 or 5 -> 5 6 adds nothing, so those stay bounded
+    assert_eq!(ranges.get("1").copied(), range(2, Some(2)));
+    assert_eq!(ranges.get("5").copied(), range(2, Some(2)));
+    assert_eq!(ranges.get("2").copied(), range(0, None));
+    assert_eq!(ranges.get("4").copied(), None);
+}
+
+#[test]
+fn enumeration_is_shortlex_and_counts_ambiguous_strings_once() {
+    let grammar = Grammar::from_input(AMBIGUOUS).unwrap();
+    let strings: Vec<String> = language::enumerate(&grammar, "0", None).collect();
+    assert_eq!(strings, vec!["aa"]);
+    assert_eq!(language::count(&grammar, "0", None), Some(1));
+
+    let grammar =
+        Grammar::from_input("0: 1 0 | 2\n1: 3 | 4\n2: 5 | \"\"\n3: \"a\"\n4: \"b\"\n5: \"c\"")
+            .unwrap();
+    let strings: Vec<String> = language::enumerate(&grammar, "0", None).take(5).collect();
+    assert_eq!(strings, vec!["", "a", "b", "c", "aa"]);
+    assert_eq!(language::count(&grammar, "0", None), None);
+    // every run of a and b, with or without a c on the end: 2 + 4 + 8 + 8
+    assert_eq!(language::count(&grammar, "0", Some(3)), Some(22));
+}
+
+#[test]
+fn enumeration_matches_the_parser() {
+    let grammar = Grammar::from_input(
+        "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"",
+    )
+    .unwrap();
+    let strings: Vec<String> = language::enumerate(&grammar, "0", None).collect();
+    assert_eq!(strings.len(), 8);
+    assert!(strings.iter().all(|s| earley::recognize(&grammar, s)));
+    assert_eq!(language::count(&grammar, "1", Some(3)), Some(0));
+}
@@ -0,0 +1,219 @@
+use advent2020::day_twenty::assembly::{self, AssemblyError};
+use advent2020::day_twenty::image::{self, Export, ImageFormat};
+use advent2020::day_twenty::pattern::{self, Pattern};
+use advent2020::day_twenty::tiles::{Orientation, Tile};
+use advent2020::day_twenty::DayTwentySolver;
+use advent2020::Solver;
+use std::path::PathBuf;
+
+// just enough of a parser for a well-formed file, the solver's own checks the rest
+fn test_tiles() -> Vec<Tile> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../inputs/20/test.txt");
+    let input = std::fs::read_to_string(path).unwrap();
+    input
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let mut lines = block.lines();
+            let header = lines.next().unwrap();
+            let id = header["Tile ".len()..header.len() - 1].parse().unwrap();
+            let data = lines
+                .map(|line| line.chars().map(|c| c == '#').collect())
+                .collect();
+            Tile::new(id, data)
+        })
+        .collect()
+}
+
+#[test]
+fn orientations_form_a_group() {
+    assert!(Orientation::ALL.contains(&Orientation::IDENTITY));
+    for a in &Orientation::ALL {
+        assert_eq!(a.compose(Orientation::IDENTITY), *a);
+        assert_eq!(Orientation::IDENTITY.compose(*a), *a);
+        assert_eq!(a.compose(a.inverse()), Orientation::IDENTITY);
+        assert_eq!(a.inverse().compose(*a), Orientation::IDENTITY);
+        for b in &Orientation::ALL {
+            for c in &Orientation::ALL {
+                assert_eq!(a.compose(*b).compose(*c), a.compose(b.compose(*c)));
+            }
+        }
+    }
+}
+
+#[test]
+fn every_orientation_of_every_tile_round_trips() {
+    let tiles = test_tiles();
+    assert_eq!(tiles.len(), 9);
+    for tile in &tiles {
+        let mut seen = vec![];
+        for orientation in &Orientation::ALL {
+            let oriented = orientation.apply_to_grid(&tile.data);
+            assert_eq!(orientation.inverse().apply_to_grid(&oriented), tile.data);
+            // the sides can be worked out without touching the grid
+            assert_eq!(
+                Tile::new(tile.id, oriented.clone()).sides(),
+                &orientation.apply_to_sides(tile.sides()),
+                "tile {} {:?}",
+                tile.id,
+                orientation
+            );
+            for then in &Orientation::ALL {
+                assert_eq!(
+                    then.apply_to_grid(&oriented),
+                    orientation.compose(*then).apply_to_grid(&tile.data)
+                );
+            }
+            assert!(!seen.contains(&oriented), "tile {} is symmetric", tile.id);
+            seen.push(oriented);
+        }
+    }
+}
+
+#[test]
+fn matched_tiles_line_up() {
+    let tiles = test_tiles();
+    let mut matches = 0;
+    for tile in &tiles {
+        for other in tiles.iter().filter(|other| other.id != tile.id) {
+            if let Some((side, placed)) = tile.get_matched_tile(other) {
+                let last = tile.size() - 1;
+                let (ours, theirs): (Vec<bool>, Vec<bool>) = match side {
+                    0 => (tile.data[0].clone(), placed.data[last].clone()),
+                    1 => (col(&tile.data, last), col(&placed.data, 0)),
+                    2 => (tile.data[last].clone(), placed.data[0].clone()),
+                    _ => (col(&tile.data, 0), col(&placed.data, last)),
+                };
+                assert_eq!(ours, theirs, "{} against {}", tile.id, other.id);
+                matches += 1;
+            }
+        }
+    }
+    // 12 shared edges in a 3x3 grid, found from both sides
+    assert_eq!(matches, 24);
+}
+
+fn col(data: &[Vec<bool>], idx: usize) -> Vec<bool> {
+    data.iter().map(|row| row[idx]).collect()
+}
+
+#[test]
+fn rectangles_swap_sides_when_turned() {
+    let grid = vec![vec![1, 2, 3], vec![4, 5, 6]];
+    let turned = Orientation::new(1, false).apply_to_grid(&grid);
+    assert_eq!(turned, vec![vec![4, 1], vec![5, 2], vec![6, 3]]);
+    for orientation in &Orientation::ALL {
+        let oriented = orientation.apply_to_grid(&grid);
+        assert_eq!(orientation.inverse().apply_to_grid(&oriented), grid);
+    }
+}
+
+#[test]
+fn assembly_finds_the_corners() {
+    let tiles = test_tiles();
+    let layout = assembly::assemble(&tiles, None).unwrap();
+    assert_eq!((layout.width, layout.height), (3, 3));
+    let mut corners = layout.corners();
+    corners.sort_unstable();
+    assert_eq!(corners, [1171, 1951, 2971, 3079]);
+    assert_eq!(layout.stitch(1).len(), 24);
+
+    assert_eq!(
+        assembly::assemble(&[], None).err(),
+        Some(AssemblyError::NoTiles)
+    );
+    assert_eq!(
+        assembly::assemble(&tiles, Some((5, 2))).err(),
+        Some(AssemblyError::TooFewTiles {
+            needed: 10,
+            found: 9
+        })
+    );
+}
+
+#[test]
+fn images_export_with_and_without_borders() {
+    let layout = assembly::assemble(&test_tiles(), None).unwrap();
+    let export = |format, keep_borders, tile_ids| {
+        let options = Export {
+            format,
+            keep_borders,
+            tile_ids,
+        };
+        image::export(&layout, 1, &options)
+    };
+
+    let pbm = export(ImageFormat::Pbm, false, false);
+    let lines: Vec<&str> = pbm.lines().collect();
+    assert_eq!(lines[..3], ["P1", "# 3x3 tiles of 8x8", "24 24"]);
+    assert_eq!(lines.len(), 3 + 24);
+    let ones = pbm
+        .lines()
+        .skip(3)
+        .flat_map(|line| line.chars())
+        .filter(|c| *c == '1');
+    assert_eq!(ones.count(), 303);
+
+    // with the borders kept, the top left cell is border and the one inside it isn't
+    let pgm = export(ImageFormat::Pgm, true, false);
+    let shades: Vec<u32> = pgm
+        .split_whitespace()
+        .skip(9) // P2, the comment, the size and the max value
+        .map(|v| v.parse().unwrap())
+        .collect();
+    assert_eq!(shades.len(), 30 * 30);
+    assert!(shades[0] == 64 || shades[0] == 192);
+    assert!(shades[31] == 0 || shades[31] == 255);
+    assert!(pgm.lines().all(|line| line.len() <= 70));
+
+    let ascii = export(ImageFormat::Ascii, false, true);
+    let lines: Vec<&str> = ascii.lines().collect();
+    assert_eq!(lines.len(), 24 + 2);
+    assert_eq!(lines[0].len(), 24 + 2);
+    for (row, ids) in layout.tiles.iter().enumerate() {
+        for tile in ids {
+            assert!(lines[row * 9 + 4].contains(&tile.id.to_string()));
+        }
+    }
+}
+
+#[test]
+fn overlapping_matches_only_count_once() {
+    // two sea monsters a column apart, sharing four cells
+    let monster = Pattern::sea_monster();
+    let mut image = vec![vec![false; 21]; 3];
+    for (row, col) in &monster.cells {
+        image[*row][*col] = true;
+        image[*row][*col + 1] = true;
+    }
+    let matches = monster.find(&image);
+    assert_eq!(matches.len(), 2);
+    assert_eq!((matches[0].row, matches[0].col), (0, 0));
+    assert_eq!(matches[0].orientation, Orientation::IDENTITY);
+    assert_eq!(pattern::roughness(&image, &matches), 0);
+
+    // a symmetric pattern isn't found again in the orientations that look the same
+    let domino = Pattern::parse("##").unwrap();
+    let image = vec![vec![true, true, true]];
+    let matches = domino.find(&image);
+    assert_eq!(matches.len(), 2);
+    assert_eq!(pattern::roughness(&image, &matches), 0);
+}
+
+#[test]
+fn patterns_are_trimmed_and_turned() {
+    let pattern = Pattern::parse("\n  .#\n  ##\n").unwrap();
+    assert_eq!(pattern.cells, vec![(0, 1), (1, 0), (1, 1)]);
+    assert_eq!((pattern.height, pattern.width), (2, 2));
+    let turned = pattern.oriented(Orientation::new(1, false));
+    assert_eq!(turned.cells, vec![(0, 0), (1, 0), (1, 1)]);
+    assert!(Pattern::parse("...\n").is_err());
+}
+
+#[test]
+fn tiles_without_rows_are_rejected() {
+    for input in &["Tile 1:\n\n", "Tile 1:\n\nTile 2:\n###\n#.#\n###\n"] {
+        let err = DayTwentySolver::from_input(input).err().unwrap();
+        assert!(err.to_string().contains("tile 1 has no rows"), "{}", err);
+    }
+}